@@ -0,0 +1,57 @@
+// the figures as quads in map space, see figure_render.rs
+struct Metadata{
+    time: f32,
+    map_zoom: f32,
+    map_translation: vec2f,       // the movement of the map in pixels
+    window_size: vec2f,           // window size in pixels
+    mouse_pos: vec2f,
+    dnd_map_movacc: vec2f,        // dnd movement accumulator
+    fog_opacity: f32,             // 1 for the players, less for the GM
+}
+struct Figure{
+    @location(0) position: vec2f, // center in map pixels
+    @location(1) size: f32,       // edge length in map pixels
+    @location(2) alpha: f32,
+    @location(3) uv: vec4f,       // the token in the atlas, left top right bottom
+}
+struct VertexOut{
+    @builtin(position) position: vec4f,
+    @location(0) uv: vec2f,
+    @location(1) alpha: f32,
+}
+
+@group(0) @binding(0) var<uniform> metadata: Metadata;
+
+@group(1) @binding(0) var token_sampler: sampler;
+@group(1) @binding(1) var token_atlas: texture_2d<f32>;
+
+@vertex
+fn vertexMain(@builtin(vertex_index) vidx: u32, figure: Figure) -> VertexOut {
+    const corners = array<vec2<f32>, 6> (
+        vec2f(0, 0),
+        vec2f(1, 0),
+        vec2f(0, 1),
+        vec2f(0, 1),
+        vec2f(1, 0),
+        vec2f(1, 1),
+    );
+    var corner = corners[vidx];
+    var map_pos = figure.position + (corner - vec2f(0.5)) * figure.size;
+
+    // the inverse of the transform in render_map.wgsl
+    var center = metadata.window_size / 2.;
+    var screen = (map_pos - center) / metadata.map_zoom + center - metadata.map_translation;
+    var ndc = screen / metadata.window_size * 2. - vec2f(1.);
+
+    var out: VertexOut;
+    out.position = vec4f(ndc.x, -ndc.y, 0., 1.);
+    out.uv = mix(figure.uv.xy, figure.uv.zw, corner);
+    out.alpha = figure.alpha;
+    return out;
+}
+
+@fragment
+fn fragmentMain(in: VertexOut) -> @location(0) vec4<f32> {
+    var color = textureSample(token_atlas, token_sampler, in.uv);
+    return vec4f(color.rgb, color.a * in.alpha);
+}
@@ -5,6 +5,7 @@ struct Metadata{
     window_size: vec2f,           // window size in pixels
     mouse_pos: vec2f,
     dnd_map_movacc: vec2f,        // dnd movement accumulator
+    fog_opacity: f32,             // 1 for the players, less for the GM
 }
 struct VertexOut{
     @builtin(position) position: vec4f,
@@ -16,6 +17,12 @@ struct VertexOut{
 @group(1) @binding(0) var map_sampler: sampler;         // the sampler of the map
 @group(1) @binding(1) var map_texture: texture_2d<f32>; // the map texture
 
+@group(2) @binding(0) var fog_sampler: sampler;
+@group(2) @binding(1) var explored_texture: texture_2d<f32>; // ever seen by a figure
+@group(2) @binding(2) var visible_texture: texture_2d<f32>;  // seen right now
+
+const DIMMED = 0.4;                                          // brightness of explored areas nobody sees
+
 fn random2(st: vec2f) -> vec2f{
     var out = vec2( dot(st,vec2f(127.1,311.7)),
               dot(st,vec2f(269.5,183.3)) );
@@ -62,7 +69,14 @@ fn fragmentMain(@location(0) uv: vec2<f32>) -> @location(0) vec4<f32> {
 
     // return vec4f(map_uv, 0.,1.);
     var result = textureSample(map_texture, map_sampler, map_uv).rgb;
-    return vec4f(mix(result, schleier(uv), 0.2),1.);
+    var explored = textureSample(explored_texture, fog_sampler, map_uv).r;
+    var visible = textureSample(visible_texture, fog_sampler, map_uv).r;
+
+    // unexplored areas are hidden behind the veil, explored ones nobody sees right now are dimmed
+    var veil = vec3f(0.05) + 0.1 * schleier(uv);
+    var fogged = mix(result * DIMMED, result, visible);
+    fogged = mix(veil, fogged, explored);
+    return vec4f(mix(result, fogged, metadata.fog_opacity), 1.);
 }
 
 @vertex
@@ -0,0 +1,35 @@
+// draws what the figures see into the fog masks, see fog.rs
+struct RevealUniform{
+    map_size: vec2f,              // map size in pixels
+}
+struct VertexIn{
+    @location(0) position: vec2f, // map pixels, y pointing down
+    @location(1) visible: f32,
+}
+struct VertexOut{
+    @builtin(position) position: vec4f,
+    @location(0) visible: f32,
+}
+struct FragmentOut{
+    @location(0) explored: vec4f,
+    @location(1) visible: vec4f,
+}
+
+@group(0) @binding(0) var<uniform> reveal: RevealUniform;
+
+@vertex
+fn vertexMain(in: VertexIn) -> VertexOut {
+    var ndc = in.position / reveal.map_size * 2. - vec2f(1.);
+    var out: VertexOut;
+    out.position = vec4f(ndc.x, -ndc.y, 0., 1.);
+    out.visible = in.visible;
+    return out;
+}
+
+@fragment
+fn fragmentMain(in: VertexOut) -> FragmentOut {
+    var out: FragmentOut;
+    out.explored = vec4f(1.);
+    out.visible = vec4f(in.visible);
+    return out;
+}
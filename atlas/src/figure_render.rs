@@ -1,62 +1,474 @@
+//! Figures on the map. Their tokens are packed into one texture atlas and drawn as instanced
+//! quads in map space, so they move and zoom with the map.
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, bail};
 use bytemuck::{Pod, Zeroable};
-use cgmath::Vector2;
-use wgpu::RenderPipeline;
+use image::{
+    Rgba, RgbaImage,
+    imageops::{self, FilterType},
+};
+use tracing::error;
+use wgpu::{RenderPipeline, util::DeviceExt};
+
+use crate::{
+    maprender::Metadata,
+    renderpass::{ColorRenderPass, load_shader},
+    scenario::FigureConfig,
+    uniform::UniformBuffer,
+};
+
+const SHADER_NAME: &str = "render_figures.wgsl";
+/// edge length of a token in the atlas, a power of two for the mip levels
+const TOKEN_SIZE: u32 = 128;
+/// down to 8 pixels per token
+const MIP_LEVELS: u32 = 5;
+/// for the discs of figures without an image
+const DISC_COLORS: &[[u8; 3]] = &[
+    [180, 40, 40],
+    [40, 90, 180],
+    [40, 150, 60],
+    [200, 160, 30],
+    [130, 60, 160],
+    [30, 150, 150],
+];
+
+/// where a token comes from, figures with the same source share one place in the atlas
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum TokenSource {
+    Image(PathBuf),
+    Disc([u8; 3]),
+}
 
-use crate::{renderpass::ColorRenderPass, uniform::UniformBuffer};
+/// Every distinct token of `figures` once, a coloured disc for those without an image,
+/// and the index of each figure's token.
+pub fn load_tokens(figures: &[FigureConfig]) -> anyhow::Result<(Vec<RgbaImage>, Vec<usize>)> {
+    let mut tokens = vec![];
+    let mut sources: HashMap<TokenSource, usize> = HashMap::new();
+    let mut figure_tokens = vec![];
+    for (i, figure) in figures.iter().enumerate() {
+        let source = match &figure.image {
+            Some(path) => TokenSource::Image(path.clone()),
+            None => TokenSource::Disc(DISC_COLORS[i % DISC_COLORS.len()]),
+        };
+        let token = match sources.get(&source) {
+            Some(&token) => token,
+            None => {
+                tokens.push(match &source {
+                    TokenSource::Image(path) => image::open(path)
+                        .map(|img| {
+                            imageops::resize(
+                                &img.into_rgba8(),
+                                TOKEN_SIZE,
+                                TOKEN_SIZE,
+                                FilterType::Triangle,
+                            )
+                        })
+                        .with_context(|| format!("could not load token {}", path.display()))?,
+                    TokenSource::Disc(color) => disc(*color),
+                });
+                sources.insert(source, tokens.len() - 1);
+                tokens.len() - 1
+            }
+        };
+        figure_tokens.push(token);
+    }
+    Ok((tokens, figure_tokens))
+}
+
+fn disc(color: [u8; 3]) -> RgbaImage {
+    let radius = TOKEN_SIZE as f32 / 2.;
+    RgbaImage::from_fn(TOKEN_SIZE, TOKEN_SIZE, |x, y| {
+        let d = ((x as f32 + 0.5 - radius).powi(2) + (y as f32 + 0.5 - radius).powi(2)).sqrt();
+        // a soft edge and a darker rim
+        let alpha = (radius - d).clamp(0., 1.);
+        let shade = if d > radius * 0.85 { 0.6 } else { 1. };
+        let [r, g, b] = color.map(|c| (c as f32 * shade) as u8);
+        Rgba([r, g, b, (alpha * 255.) as u8])
+    })
+}
+
+/// Packs tokens of [`TOKEN_SIZE`] into a square-ish grid, with the uv rectangle
+/// `[left, top, right, bottom]` of every token.
+/// Fails if the atlas would be wider or higher than `max_dimension`.
+pub fn pack(
+    tokens: &[RgbaImage],
+    max_dimension: u32,
+) -> anyhow::Result<(RgbaImage, Vec<[f32; 4]>)> {
+    let max_cols = (max_dimension / TOKEN_SIZE).max(1);
+    let cols = ((tokens.len() as f32).sqrt().ceil().max(1.) as u32).min(max_cols);
+    let rows = (tokens.len() as u32).div_ceil(cols).max(1);
+    if cols * TOKEN_SIZE > max_dimension || rows * TOKEN_SIZE > max_dimension {
+        bail!(
+            "{} different tokens do not fit into a texture of {max_dimension} pixels, \
+             let more figures share an image",
+            tokens.len()
+        );
+    }
+    let mut atlas = RgbaImage::new(cols * TOKEN_SIZE, rows * TOKEN_SIZE);
+    let (w, h) = (atlas.width() as f32, atlas.height() as f32);
+    let uvs = tokens
+        .iter()
+        .enumerate()
+        .map(|(i, token)| {
+            let (x, y) = (i as u32 % cols * TOKEN_SIZE, i as u32 / cols * TOKEN_SIZE);
+            imageops::replace(&mut atlas, token, x as i64, y as i64);
+            // half a texel in, so nothing bleeds over from the neighbours
+            let (x, y, size) = (x as f32, y as f32, TOKEN_SIZE as f32);
+            [
+                (x + 0.5) / w,
+                (y + 0.5) / h,
+                (x + size - 0.5) / w,
+                (y + size - 0.5) / h,
+            ]
+        })
+        .collect();
+    Ok((atlas, uvs))
+}
 
 #[repr(C)]
 #[derive(Debug, Clone, Copy, Pod, Zeroable)]
-struct FigureUniform {
-    figure_origin: [Vector2<f32>; 16],
-    zoom: [f32; 16],
-    window_size: Vector2<f32>,
-    figure_extent: Vector2<f32>,
+struct FigureInstance {
+    /// center in map pixels
+    position: [f32; 2],
+    /// edge length in map pixels
+    size: f32,
+    alpha: f32,
+    uv: [f32; 4],
+}
 
-    time: f32,
-    mouse_pos: f32,
-    dragged: i32,
+/// one figure to draw in the next frame
+#[derive(Debug, Clone, Copy)]
+pub struct FigureDraw {
+    /// index of the figure in the scenario
+    pub figure: usize,
+    pub position: [f32; 2],
+    pub size: f32,
+    pub alpha: f32,
 }
+
+#[derive(Clone)]
 struct SamplerData {
     bind_group: wgpu::BindGroup,
 }
+
 impl SamplerData {
-    //TODO
+    fn new(device: &wgpu::Device, queue: &wgpu::Queue, atlas: &RgbaImage) -> Self {
+        let (w, h) = atlas.dimensions();
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            label: Some("token atlas"),
+            mip_level_count: MIP_LEVELS,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            size: wgpu::Extent3d {
+                depth_or_array_layers: 1,
+                width: w,
+                height: h,
+            },
+            usage: wgpu::TextureUsages::COPY_DST | wgpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        });
+        for level in 0..MIP_LEVELS {
+            let (w, h) = (w >> level, h >> level);
+            let image = imageops::resize(atlas, w, h, FilterType::Triangle);
+            queue.write_texture(
+                wgpu::TexelCopyTextureInfo {
+                    texture: &texture,
+                    mip_level: level,
+                    aspect: wgpu::TextureAspect::All,
+                    origin: wgpu::Origin3d::ZERO,
+                },
+                image.as_raw().as_slice(),
+                wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(w * 4),
+                    rows_per_image: Some(h),
+                },
+                wgpu::Extent3d {
+                    depth_or_array_layers: 1,
+                    width: w,
+                    height: h,
+                },
+            );
+        }
+
+        let texview = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: None,
+            layout: &Self::bglayout(device),
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::Sampler(&sampler),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(&texview),
+                },
+            ],
+        });
+        Self { bind_group }
+    }
+
+    fn bglayout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
+        device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: None,
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    count: None,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    count: None,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                },
+            ],
+        })
+    }
+}
+
+/// The tokens of all figures on the GPU, made once and shared by the views.
+#[derive(Clone)]
+pub struct TokenAtlas {
+    sampler_data: SamplerData,
+    /// per figure
+    uvs: Vec<[f32; 4]>,
+}
+
+impl TokenAtlas {
+    pub fn new(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        figures: &[FigureConfig],
+    ) -> anyhow::Result<Self> {
+        let (tokens, figure_tokens) = load_tokens(figures)?;
+        let (atlas, uvs) = pack(&tokens, device.limits().max_texture_dimension_2d)?;
+        Ok(Self {
+            sampler_data: SamplerData::new(device, queue, &atlas),
+            uvs: figure_tokens.into_iter().map(|token| uvs[token]).collect(),
+        })
+    }
 }
+
 pub struct FigureRenderpass {
     pub pipeline: RenderPipeline,
-    uniform: Option<UniformBuffer<FigureUniform>>,
+    uniform: Option<UniformBuffer<Metadata>>,
+    atlas: TokenAtlas,
+    instances: Vec<FigureInstance>,
     color_format: wgpu::TextureFormat,
+    shader_dir: PathBuf,
 }
+
 impl FigureRenderpass {
-    fn new(
-        figures: Vec<&str>,
+    pub fn new(
+        atlas: &TokenAtlas,
         color_format: wgpu::TextureFormat,
         device: &wgpu::Device,
-        queue: &wgpu::Queue,
-    ) {
-        // TODO:
-        // 1. build a texture atlas from the figures
-        // 2. load the texture atlas into memory
-        // 3. set up the render pass
-        todo!()
+        shader_dir: &Path,
+    ) -> anyhow::Result<Self> {
+        Ok(Self {
+            pipeline: Self::create_pipeline(device, color_format, shader_dir)?,
+            uniform: None,
+            atlas: atlas.clone(),
+            instances: vec![],
+            color_format,
+            shader_dir: shader_dir.to_path_buf(),
+        })
+    }
+
+    /// what the next [`render`](ColorRenderPass::render) draws, later figures on top
+    pub fn set_figures(&mut self, figures: impl IntoIterator<Item = FigureDraw>) {
+        self.instances = figures
+            .into_iter()
+            .map(|figure| FigureInstance {
+                position: figure.position,
+                size: figure.size,
+                alpha: figure.alpha,
+                uv: self.atlas.uvs[figure.figure],
+            })
+            .collect();
     }
-    fn reload_shaders(&mut self, device: &wgpu::Device) {
-        self.pipeline = Self::create_pipeline(device, self.color_format);
+
+    /// keeps the old pipeline if the shader cannot be read
+    pub fn reload_shaders(&mut self, device: &wgpu::Device) {
+        match Self::create_pipeline(device, self.color_format, &self.shader_dir) {
+            Ok(pipeline) => self.pipeline = pipeline,
+            Err(e) => error!("{e:#}"),
+        }
     }
 }
 
-impl ColorRenderPass<FigureUniform> for FigureRenderpass {
-    fn create_pipeline(device: &wgpu::Device, cf: wgpu::TextureFormat) -> wgpu::RenderPipeline {
-        todo!()
+impl ColorRenderPass<Metadata> for FigureRenderpass {
+    fn create_pipeline(
+        device: &wgpu::Device,
+        cf: wgpu::TextureFormat,
+        shader_dir: &Path,
+    ) -> anyhow::Result<wgpu::RenderPipeline> {
+        let layout = wgpu::PipelineLayoutDescriptor {
+            bind_group_layouts: &[
+                &UniformBuffer::<Metadata>::bind_group_layout(device),
+                &SamplerData::bglayout(device),
+            ],
+            ..Default::default()
+        };
+        let shader = load_shader(device, shader_dir, SHADER_NAME)?;
+        let rpl_desc = wgpu::RenderPipelineDescriptor {
+            layout: Some(&device.create_pipeline_layout(&layout)),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vertexMain"),
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+                buffers: &[wgpu::VertexBufferLayout {
+                    array_stride: std::mem::size_of::<FigureInstance>() as u64,
+                    step_mode: wgpu::VertexStepMode::Instance,
+                    attributes: &wgpu::vertex_attr_array![
+                        0 => Float32x2, 1 => Float32, 2 => Float32, 3 => Float32x4
+                    ],
+                }],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fragmentMain"),
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+                targets: &[Some(wgpu::ColorTargetState {
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::all(),
+                    format: cf,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            cache: None,
+            label: Some("figure pipeline"),
+            multiview: None,
+        };
+        Ok(device.create_render_pipeline(&rpl_desc))
     }
+
     fn render(
         &mut self,
         device: &wgpu::Device,
         encoder: &mut wgpu::CommandEncoder,
         surfacetv: &wgpu::TextureView,
         _: Option<&wgpu::TextureView>,
-        ued: FigureUniform,
+        ued: Metadata,
     ) {
-        todo!()
+        if self.instances.is_empty() {
+            return;
+        }
+        let uniform = self.uniform.insert(UniformBuffer::new(device, ued, None));
+        let instances = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("figure instances"),
+            contents: bytemuck::cast_slice(&self.instances),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Figure Render Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                depth_slice: None,
+                ops: wgpu::Operations {
+                    store: wgpu::StoreOp::Store,
+                    load: wgpu::LoadOp::Load,
+                },
+                resolve_target: None,
+                view: surfacetv,
+            })],
+            depth_stencil_attachment: None,
+            occlusion_query_set: None,
+            timestamp_writes: None,
+        });
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, uniform.bind_group(), &[]);
+        pass.set_bind_group(1, &self.atlas.sampler_data.bind_group, &[]);
+        pass.set_vertex_buffer(0, instances.slice(..));
+        pass.draw(0..6, 0..self.instances.len() as u32);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use image::{Rgba, RgbaImage};
+
+    use super::{DISC_COLORS, TOKEN_SIZE, disc, load_tokens, pack};
+    use crate::scenario::FigureConfig;
+
+    #[test]
+    fn packs_tokens() {
+        let tokens: Vec<_> = DISC_COLORS.iter().take(5).map(|&c| disc(c)).collect();
+        // a transparent corner, an opaque middle
+        assert_eq!(tokens[0].get_pixel(0, 0).0[3], 0);
+        assert_eq!(
+            tokens[0].get_pixel(TOKEN_SIZE / 2, TOKEN_SIZE / 2).0[3],
+            255
+        );
+
+        let (atlas, uvs) = pack(&tokens, 8192).unwrap();
+        assert_eq!(atlas.dimensions(), (3 * TOKEN_SIZE, 2 * TOKEN_SIZE));
+        assert_eq!(uvs.len(), 5);
+        for (i, uv) in uvs.iter().enumerate() {
+            assert!(uv.iter().all(|c| (0. ..=1.).contains(c)));
+            assert!(uv[0] < uv[2] && uv[1] < uv[3]);
+            // the middle of every cell is its token's colour
+            let x = ((uv[0] + uv[2]) / 2. * atlas.width() as f32) as u32;
+            let y = ((uv[1] + uv[3]) / 2. * atlas.height() as f32) as u32;
+            assert_eq!(atlas.get_pixel(x, y).0[..3], DISC_COLORS[i]);
+        }
+        for pair in uvs.windows(2) {
+            assert_ne!(pair[0], pair[1]);
+        }
+
+        // a narrow device stacks them higher, until that does not fit either
+        let (narrow, _) = pack(&tokens[..4], 2 * TOKEN_SIZE).unwrap();
+        assert_eq!(narrow.dimensions(), (2 * TOKEN_SIZE, 2 * TOKEN_SIZE));
+        assert!(pack(&tokens, 2 * TOKEN_SIZE).is_err());
+
+        let (empty, uvs) = pack(&[], 8192).unwrap();
+        assert_eq!(empty.dimensions(), (TOKEN_SIZE, TOKEN_SIZE));
+        assert!(uvs.is_empty());
+    }
+
+    #[test]
+    fn figures_share_tokens() {
+        let path = std::env::temp_dir().join("atlas_shared_token.png");
+        RgbaImage::from_pixel(4, 4, Rgba([200, 0, 0, 255]))
+            .save(&path)
+            .unwrap();
+        let figure = |image: Option<&std::path::Path>| FigureConfig {
+            name: "Goblin".to_string(),
+            image: image.map(|p| p.to_path_buf()),
+            position: [0., 0.],
+            size: 1.,
+            sight: 10.,
+            hidden: false,
+        };
+        // a horde of goblins with one image and two without
+        let mut figures: Vec<_> = (0..20).map(|_| figure(Some(&path))).collect();
+        figures.extend([figure(None), figure(None)]);
+        let (tokens, figure_tokens) = load_tokens(&figures).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(tokens.len(), 3);
+        assert!(figure_tokens[..20].iter().all(|&t| t == 0));
+        assert_eq!(&figure_tokens[20..], [1, 2]);
+        assert_eq!(tokens[0].dimensions(), (TOKEN_SIZE, TOKEN_SIZE));
     }
 }
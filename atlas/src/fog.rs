@@ -0,0 +1,291 @@
+//! Fog of war. Two masks in the size of the map remember what the figures have seen
+//! (`explored`, never cleared) and what they see right now (`visible`, redrawn on every update).
+//! Both are drawn on the GPU from triangles in map pixels and sampled by the map shader.
+
+use std::path::{Path, PathBuf};
+
+use bytemuck::{Pod, Zeroable};
+use cgmath::Vector2;
+use tracing::error;
+use wgpu::util::DeviceExt;
+
+use crate::{renderpass::load_shader, uniform::UniformBuffer};
+
+const SHADER_NAME: &str = "reveal.wgsl";
+/// map pixels per fog texel in each direction, the fog does not need the full resolution
+const FOG_SCALE: u32 = 2;
+const FOG_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::R8Unorm;
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+struct RevealUniform {
+    map_size: Vector2<f32>,
+    _padding: Vector2<f32>,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+struct RevealVertex {
+    position: [f32; 2],
+    /// 1 if the area is seen right now, 0 if it only counts as explored
+    visible: f32,
+}
+
+/// an area to uncover, as a triangle list in map pixels
+#[derive(Debug, Clone, Default)]
+pub struct Reveal {
+    pub triangles: Vec<[f32; 2]>,
+    pub visible: bool,
+}
+
+impl Reveal {
+    /// triangles from `center` to every edge of `outline`, which has to be star shaped around it
+    pub fn fan(center: [f32; 2], outline: &[[f32; 2]], visible: bool) -> Self {
+        let triangles = (0..outline.len())
+            .flat_map(|i| [center, outline[i], outline[(i + 1) % outline.len()]])
+            .collect();
+        Self { triangles, visible }
+    }
+}
+
+pub struct FogOfWar {
+    explored: wgpu::TextureView,
+    visible: wgpu::TextureView,
+    uniform: UniformBuffer<RevealUniform>,
+    pipeline: wgpu::RenderPipeline,
+    /// for the passes that show the fog
+    bind_group: wgpu::BindGroup,
+    shader_dir: PathBuf,
+}
+
+fn attachment(
+    view: &wgpu::TextureView,
+    load: wgpu::LoadOp<wgpu::Color>,
+) -> Option<wgpu::RenderPassColorAttachment<'_>> {
+    Some(wgpu::RenderPassColorAttachment {
+        view,
+        depth_slice: None,
+        resolve_target: None,
+        ops: wgpu::Operations {
+            load,
+            store: wgpu::StoreOp::Store,
+        },
+    })
+}
+
+impl FogOfWar {
+    pub fn new(
+        device: &wgpu::Device,
+        (width, height): (u32, u32),
+        shader_dir: &Path,
+    ) -> anyhow::Result<Self> {
+        let size = wgpu::Extent3d {
+            width: (width / FOG_SCALE).max(1),
+            height: (height / FOG_SCALE).max(1),
+            depth_or_array_layers: 1,
+        };
+        let mask = |label: &str| {
+            device
+                .create_texture(&wgpu::TextureDescriptor {
+                    label: Some(label),
+                    size,
+                    mip_level_count: 1,
+                    sample_count: 1,
+                    dimension: wgpu::TextureDimension::D2,
+                    format: FOG_FORMAT,
+                    usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                        | wgpu::TextureUsages::TEXTURE_BINDING,
+                    view_formats: &[],
+                })
+                .create_view(&wgpu::TextureViewDescriptor::default())
+        };
+        let explored = mask("explored");
+        let visible = mask("visible");
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("fog bind group"),
+            layout: &Self::bglayout(device),
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::Sampler(&sampler),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(&explored),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::TextureView(&visible),
+                },
+            ],
+        });
+        let uniform = UniformBuffer::new(
+            device,
+            RevealUniform {
+                map_size: Vector2::new(width as f32, height as f32),
+                _padding: Vector2::new(0., 0.),
+            },
+            Some("reveal uniform"),
+        );
+        Ok(Self {
+            explored,
+            visible,
+            uniform,
+            pipeline: Self::create_pipeline(device, shader_dir)?,
+            bind_group,
+            shader_dir: shader_dir.to_path_buf(),
+        })
+    }
+
+    /// sampler, explored and visible mask, for group of a pass that shows the fog
+    pub fn bglayout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
+        let mask = |binding| wgpu::BindGroupLayoutEntry {
+            binding,
+            count: None,
+            ty: wgpu::BindingType::Texture {
+                sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                view_dimension: wgpu::TextureViewDimension::D2,
+                multisampled: false,
+            },
+            visibility: wgpu::ShaderStages::FRAGMENT,
+        };
+        device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("fog bind group layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    count: None,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                },
+                mask(1),
+                mask(2),
+            ],
+        })
+    }
+
+    pub fn bind_group(&self) -> &wgpu::BindGroup {
+        &self.bind_group
+    }
+
+    fn create_pipeline(
+        device: &wgpu::Device,
+        shader_dir: &Path,
+    ) -> anyhow::Result<wgpu::RenderPipeline> {
+        let shader = load_shader(device, shader_dir, SHADER_NAME)?;
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            bind_group_layouts: &[&UniformBuffer::<RevealUniform>::bind_group_layout(device)],
+            ..Default::default()
+        });
+        // the masks only ever grow within one pass
+        let max = wgpu::BlendComponent {
+            src_factor: wgpu::BlendFactor::One,
+            dst_factor: wgpu::BlendFactor::One,
+            operation: wgpu::BlendOperation::Max,
+        };
+        let target = Some(wgpu::ColorTargetState {
+            format: FOG_FORMAT,
+            blend: Some(wgpu::BlendState {
+                color: max,
+                alpha: max,
+            }),
+            write_mask: wgpu::ColorWrites::all(),
+        });
+        Ok(
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some("reveal pipeline"),
+                layout: Some(&layout),
+                vertex: wgpu::VertexState {
+                    module: &shader,
+                    entry_point: Some("vertexMain"),
+                    compilation_options: wgpu::PipelineCompilationOptions::default(),
+                    buffers: &[wgpu::VertexBufferLayout {
+                        array_stride: std::mem::size_of::<RevealVertex>() as u64,
+                        step_mode: wgpu::VertexStepMode::Vertex,
+                        attributes: &wgpu::vertex_attr_array![0 => Float32x2, 1 => Float32],
+                    }],
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &shader,
+                    entry_point: Some("fragmentMain"),
+                    compilation_options: wgpu::PipelineCompilationOptions::default(),
+                    targets: &[target.clone(), target],
+                }),
+                primitive: wgpu::PrimitiveState::default(),
+                depth_stencil: None,
+                multisample: wgpu::MultisampleState::default(),
+                multiview: None,
+                cache: None,
+            }),
+        )
+    }
+
+    /// keeps the old pipeline if the shader cannot be read
+    pub fn reload_shaders(&mut self, device: &wgpu::Device) {
+        match Self::create_pipeline(device, &self.shader_dir) {
+            Ok(pipeline) => self.pipeline = pipeline,
+            Err(e) => error!("{e:#}"),
+        }
+    }
+
+    /// Adds `reveals` to the explored mask and replaces the visible mask with them.
+    pub fn update(
+        &self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        reveals: &[Reveal],
+    ) {
+        let vertices: Vec<RevealVertex> = reveals
+            .iter()
+            .flat_map(|reveal| {
+                let visible = if reveal.visible { 1. } else { 0. };
+                reveal
+                    .triangles
+                    .iter()
+                    .map(move |&position| RevealVertex { position, visible })
+            })
+            .collect();
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Reveal Render Pass"),
+            color_attachments: &[
+                attachment(&self.explored, wgpu::LoadOp::Load),
+                attachment(&self.visible, wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT)),
+            ],
+            depth_stencil_attachment: None,
+            occlusion_query_set: None,
+            timestamp_writes: None,
+        });
+        if vertices.is_empty() {
+            return;
+        }
+        let buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("reveal vertices"),
+            contents: bytemuck::cast_slice(&vertices),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, self.uniform.bind_group(), &[]);
+        pass.set_vertex_buffer(0, buffer.slice(..));
+        pass.draw(0..vertices.len() as u32, 0..1);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Reveal;
+
+    #[test]
+    fn fan_is_closed() {
+        let square = [[0., 0.], [10., 0.], [10., 10.], [0., 10.]];
+        let reveal = Reveal::fan([5., 5.], &square, true);
+        assert_eq!(reveal.triangles.len(), square.len() * 3);
+        assert!(reveal.triangles.chunks(3).all(|t| t[0] == [5., 5.]));
+        // the last triangle ends where the first one starts
+        assert_eq!(reveal.triangles[1], *reveal.triangles.last().unwrap());
+    }
+}
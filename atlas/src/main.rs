@@ -1,6 +1,8 @@
-use std::sync::Arc;
+use std::{path::PathBuf, sync::Arc};
 
+use anyhow::Context;
 use cgmath::Vector2;
+use clap::Parser;
 #[allow(unused)]
 use tracing::{debug, error, info, trace};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
@@ -12,27 +14,65 @@ use winit::{
 };
 
 use crate::{
+    figure_render::{FigureDraw, FigureRenderpass, TokenAtlas},
+    fog::{FogOfWar, Reveal},
     maprender::{MapRenderpass, Metadata},
+    notes::NoteOverlay,
+    regions::{Regions, Session},
     renderpass::ColorRenderPass,
+    scenario::{FigureConfig, Grid, Note, Scenario, Wall},
+    visibility::{Segment, door_at, segments, visibility_polygon},
 };
 
 mod figure_render;
+mod fog;
 mod maprender;
+mod notes;
+mod regions;
 mod renderpass;
+mod scenario;
 mod uniform;
+mod visibility;
+
+/// A map panel for roleplaying
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    /// the scenario file (TOML) with map, figures and regions
+    scenario: PathBuf,
+    /// where the WGSL shaders are read from, press `r` to reload them
+    #[arg(long, default_value = concat!(env!("CARGO_MANIFEST_DIR"), "/shaders"))]
+    shaders: PathBuf,
+    /// only open the GM window
+    #[arg(long)]
+    gm_only: bool,
+}
 
 struct ColorRenderpasses {
     map_bg_rp: MapRenderpass,
+    figures: FigureRenderpass,
 }
 impl ColorRenderpasses {
     fn new(
         queue: &wgpu::Queue,
         device: &wgpu::Device,
         surface_format: wgpu::TextureFormat,
-    ) -> Self {
-        Self {
-            map_bg_rp: maprender::MapRenderpass::new(surface_format, &device, &queue),
-        }
+        map: &image::RgbaImage,
+        fog: &FogOfWar,
+        tokens: &TokenAtlas,
+        shader_dir: &std::path::Path,
+    ) -> anyhow::Result<Self> {
+        Ok(Self {
+            map_bg_rp: maprender::MapRenderpass::new(
+                surface_format,
+                device,
+                queue,
+                map,
+                fog,
+                shader_dir,
+            )?,
+            figures: FigureRenderpass::new(tokens, surface_format, device, shader_dir)?,
+        })
     }
     fn render(
         &mut self,
@@ -44,60 +84,94 @@ impl ColorRenderpasses {
     ) {
         self.map_bg_rp
             .render(device, encoder, col_render_target, dep_render_target, ued);
+        self.figures
+            .render(device, encoder, col_render_target, dep_render_target, ued);
     }
     fn reload_shaders(&mut self, device: &wgpu::Device) {
         self.map_bg_rp.reload_shaders(device);
+        self.figures.reload_shaders(device);
     }
 }
 
-/// TODO: This actually has to be a "state-global" structure containing all updated data. `struct ColorRenderpasses` above takes it on himself
-/// to form this into the Uniform structs all the renderpasses require
-struct RenderState {
+/// how much of the map the GM sees through the fog
+const GM_FOG_OPACITY: f32 = 0.5;
+/// hidden figures are only shown to the GM, and see-through
+const HIDDEN_ALPHA: f32 = 0.5;
+/// the preview of where a dragged figure lands
+const GHOST_ALPHA: f32 = 0.4;
+
+/// who looks at a window
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ViewKind {
+    /// sees everything, hidden figures, notes and the map under a thin fog
+    Gamemaster,
+    /// what goes on the table, only the revealed map and visible figures
+    Player,
+}
+
+impl ViewKind {
+    fn fog_opacity(self) -> f32 {
+        match self {
+            Self::Gamemaster => GM_FOG_OPACITY,
+            Self::Player => 1.,
+        }
+    }
+}
+
+#[derive(PartialEq)]
+enum DNDState {
+    Free,
+    Left,
+    Right,
+}
+
+/// A window onto the map with its own camera. All views share the device and the fog.
+struct View {
+    kind: ViewKind,
     window: Arc<Window>,
-    device: wgpu::Device,
-    queue: wgpu::Queue,
     size: winit::dpi::PhysicalSize<u32>,
     surface: wgpu::Surface<'static>,
     surface_format: wgpu::TextureFormat,
     renderpasses: ColorRenderpasses,
+    /// only the GM reads the notes
+    notes: Option<NoteOverlay>,
     metadata: Metadata,
-    start_time: std::time::Instant,
+    dnd_state: DNDState,
+    dnd_start: Vector2<f32>,
+    /// the figure dragged with the left button, and where it would land
+    dragged: Option<usize>,
+    ghost: [f32; 2],
 }
 
-impl RenderState {
-    async fn new(window: Arc<Window>) -> RenderState {
-        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor::default());
-        let adapter = instance
-            .request_adapter(&wgpu::RequestAdapterOptions::default())
-            .await
-            .unwrap();
-        let (device, queue) = adapter
-            .request_device(&wgpu::DeviceDescriptor {
-                required_features: wgpu::Features {
-                    features_wgpu: wgpu::FeaturesWGPU::ADDRESS_MODE_CLAMP_TO_BORDER,
-                    ..Default::default()
-                },
-                ..Default::default()
-            })
-            .await
-            .unwrap();
-
+impl View {
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        kind: ViewKind,
+        window: Arc<Window>,
+        surface: wgpu::Surface<'static>,
+        adapter: &wgpu::Adapter,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        map: &image::RgbaImage,
+        fog: &FogOfWar,
+        tokens: &TokenAtlas,
+        shader_dir: &std::path::Path,
+    ) -> anyhow::Result<Self> {
         let size = window.inner_size();
-
-        let surface = instance.create_surface(window.clone()).unwrap();
-        let cap = surface.get_capabilities(&adapter);
+        let cap = surface.get_capabilities(adapter);
         let surface_format = cap.formats[0];
-
-        let crp = ColorRenderpasses::new(&queue, &device, surface_format);
-
-        let state = RenderState {
+        let renderpasses =
+            ColorRenderpasses::new(queue, device, surface_format, map, fog, tokens, shader_dir)?;
+        let notes = (kind == ViewKind::Gamemaster)
+            .then(|| NoteOverlay::new(device, surface_format.add_srgb_suffix(), &window));
+        let view = View {
+            kind,
             window,
-            device,
-            queue,
             size,
             surface,
             surface_format,
-            renderpasses: crp,
+            renderpasses,
+            notes,
             metadata: Metadata {
                 time: 0.,
                 map_zoom: 1.,
@@ -108,21 +182,22 @@ impl RenderState {
                 },
                 mouse_pos: cgmath::Vector2 { x: 0., y: 0. },
                 dnd_map_movacc: cgmath::Vector2 { x: 0., y: 0. },
+                fog_opacity: kind.fog_opacity(),
+                _padding: 0.,
             },
-            start_time: std::time::Instant::now(),
+            dnd_state: DNDState::Free,
+            dnd_start: Vector2 { x: 0., y: 0. },
+            dragged: None,
+            ghost: [0., 0.],
         };
 
         // Configure surface for the first time
-        state.configure_surface();
+        view.configure_surface(device);
 
-        state
+        Ok(view)
     }
 
-    fn get_window(&self) -> &Window {
-        &self.window
-    }
-
-    fn configure_surface(&self) {
+    fn configure_surface(&self, device: &wgpu::Device) {
         let surface_config = wgpu::SurfaceConfiguration {
             usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
             format: self.surface_format,
@@ -134,10 +209,10 @@ impl RenderState {
             desired_maximum_frame_latency: 2,
             present_mode: wgpu::PresentMode::AutoVsync,
         };
-        self.surface.configure(&self.device, &surface_config);
+        self.surface.configure(device, &surface_config);
     }
 
-    fn resize(&mut self, new_size: winit::dpi::PhysicalSize<u32>) {
+    fn resize(&mut self, device: &wgpu::Device, new_size: winit::dpi::PhysicalSize<u32>) {
         self.size = new_size;
         self.metadata.window_size = cgmath::Vector2 {
             x: new_size.width as f32,
@@ -145,10 +220,17 @@ impl RenderState {
         };
 
         // reconfigure the surface
-        self.configure_surface();
+        self.configure_surface(device);
     }
 
-    fn render(&mut self) {
+    fn render(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        time: f32,
+        figures: Vec<FigureDraw>,
+        notes: &[Note],
+    ) {
         // Create texture view
         let surface_texture = self
             .surface
@@ -162,80 +244,318 @@ impl RenderState {
                 format: Some(self.surface_format.add_srgb_suffix()),
                 ..Default::default()
             });
-        self.metadata.time = (std::time::Instant::now() - self.start_time).as_secs_f32();
-        // Renders a GREEN screen
-        let mut encoder = self.device.create_command_encoder(&Default::default());
+        self.metadata.time = time;
+        self.renderpasses.figures.set_figures(figures);
+        let mut encoder = device.create_command_encoder(&Default::default());
         // Create the renderpass which will clear the screen.
-        self.renderpasses.render(
-            &self.device,
-            &mut encoder,
-            &texture_view,
-            None,
-            self.metadata,
-        );
+        self.renderpasses
+            .render(device, &mut encoder, &texture_view, None, self.metadata);
+        if let Some(overlay) = &mut self.notes {
+            overlay.render(
+                device,
+                queue,
+                &mut encoder,
+                &texture_view,
+                &self.window,
+                &self.metadata,
+                notes,
+            );
+        }
 
         // Submit the command in the queue to execute
-        self.queue.submit([encoder.finish()]);
+        queue.submit([encoder.finish()]);
         self.window.pre_present_notify();
         surface_texture.present();
     }
 }
 
-#[derive(PartialEq)]
-enum DNDState {
-    Free,
-    Left,
-    Right,
+/// TODO: This actually has to be a "state-global" structure containing all updated data. `struct ColorRenderpasses` above takes it on himself
+/// to form this into the Uniform structs all the renderpasses require
+struct RenderState {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    views: Vec<View>,
+    fog: FogOfWar,
+    /// set when figures moved, the fog is redrawn with the next frame
+    fog_dirty: bool,
+    figures: Vec<FigureConfig>,
+    grid: Grid,
+    /// doors in here are opened and closed while playing
+    walls: Vec<Wall>,
+    /// the pieces of `walls` that block the sight right now
+    sight_blockers: Vec<Segment>,
+    regions: Regions,
+    notes: Vec<Note>,
+    start_time: std::time::Instant,
+}
+
+impl RenderState {
+    async fn new(
+        windows: Vec<(ViewKind, Arc<Window>)>,
+        scenario: &Scenario,
+        shader_dir: &std::path::Path,
+        session: PathBuf,
+    ) -> anyhow::Result<RenderState> {
+        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor::default());
+        let surfaces = windows
+            .iter()
+            .map(|(_, window)| {
+                instance
+                    .create_surface(window.clone())
+                    .context("could not create a surface for the window")
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let adapter = instance
+            .request_adapter(&wgpu::RequestAdapterOptions {
+                compatible_surface: surfaces.first(),
+                ..Default::default()
+            })
+            .await
+            .context("no suitable graphics adapter found")?;
+        let (device, queue) = adapter
+            .request_device(&wgpu::DeviceDescriptor {
+                required_features: wgpu::Features {
+                    features_wgpu: wgpu::FeaturesWGPU::ADDRESS_MODE_CLAMP_TO_BORDER,
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .await
+            .context("could not open the graphics device")?;
+
+        let map = scenario.map_image()?;
+        let fog = FogOfWar::new(&device, map.dimensions(), shader_dir)?;
+        // one atlas for all views
+        let tokens = TokenAtlas::new(&device, &queue, &scenario.figures)?;
+        let views = windows
+            .into_iter()
+            .zip(surfaces)
+            .map(|((kind, window), surface)| {
+                View::new(
+                    kind, window, surface, &adapter, &device, &queue, &map, &fog, &tokens,
+                    shader_dir,
+                )
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        Ok(RenderState {
+            device,
+            queue,
+            views,
+            fog,
+            fog_dirty: true,
+            figures: scenario.figures.clone(),
+            grid: scenario.grid,
+            walls: scenario.walls.clone(),
+            sight_blockers: segments(&scenario.walls),
+            regions: Regions::new(&scenario.regions, session)?,
+            notes: scenario.all_notes(),
+            start_time: std::time::Instant::now(),
+        })
+    }
+
+    fn view_index(&self, id: WindowId) -> Option<usize> {
+        self.views.iter().position(|view| view.window.id() == id)
+    }
+
+    /// what the party sees, hidden figures belong to the GM and reveal nothing
+    fn reveals(&mut self) -> Vec<Reveal> {
+        let party: Vec<&FigureConfig> = self.figures.iter().filter(|f| !f.hidden).collect();
+        let positions: Vec<[f32; 2]> = party.iter().map(|f| f.position).collect();
+        self.regions.enter(&positions);
+        let pixels_per_schritt = self.grid.pixels_per_schritt();
+        party
+            .iter()
+            .map(|figure| {
+                let outline = visibility_polygon(
+                    figure.position,
+                    figure.sight * pixels_per_schritt,
+                    &self.sight_blockers,
+                );
+                Reveal::fan(figure.position, &outline, true)
+            })
+            .chain(self.regions.reveals(&positions))
+            .collect()
+    }
+
+    /// opens or closes the door next to `pos`, true if there is one
+    fn toggle_door(&mut self, pos: [f32; 2]) -> bool {
+        let Some(door) = door_at(&self.walls, pos, self.grid.size / 2.) else {
+            return false;
+        };
+        let door = &mut self.walls[door];
+        door.open = !door.open;
+        info!("door {}", if door.open { "opened" } else { "closed" });
+        self.sight_blockers = segments(&self.walls);
+        self.fog_dirty = true;
+        true
+    }
+
+    /// hidden figures only for the GM
+    fn figure_draws(&self, kind: ViewKind) -> Vec<FigureDraw> {
+        self.figures
+            .iter()
+            .enumerate()
+            .filter(|(_, figure)| kind == ViewKind::Gamemaster || !figure.hidden)
+            .map(|(i, figure)| FigureDraw {
+                figure: i,
+                position: figure.position,
+                size: figure.size * self.grid.size,
+                alpha: if figure.hidden { HIDDEN_ALPHA } else { 1. },
+            })
+            .collect()
+    }
+
+    /// the topmost figure at `pos` that is shown in a view of `kind`
+    fn figure_at(&self, kind: ViewKind, pos: [f32; 2]) -> Option<usize> {
+        self.figure_draws(kind)
+            .iter()
+            .rev()
+            .find(|draw| {
+                let (dx, dy) = (draw.position[0] - pos[0], draw.position[1] - pos[1]);
+                (dx * dx + dy * dy).sqrt() <= draw.size / 2.
+            })
+            .map(|draw| draw.figure)
+    }
+
+    /// where a figure dragged to `pos` lands, `free` ignores the grid
+    fn drop_position(&self, pos: [f32; 2], free: bool) -> [f32; 2] {
+        if free { pos } else { self.grid.snap(pos) }
+    }
+
+    fn render(&mut self, view: usize) {
+        // the fog is shared, whichever view comes first updates it for all
+        if self.fog_dirty {
+            let reveals = self.reveals();
+            let mut encoder = self.device.create_command_encoder(&Default::default());
+            self.fog.update(&self.device, &mut encoder, &reveals);
+            self.queue.submit([encoder.finish()]);
+            self.fog_dirty = false;
+        }
+        let time = (std::time::Instant::now() - self.start_time).as_secs_f32();
+        let mut figures = self.figure_draws(self.views[view].kind);
+        if let Some(figure) = self.views[view].dragged {
+            figures.push(FigureDraw {
+                figure,
+                position: self.views[view].ghost,
+                size: self.figures[figure].size * self.grid.size,
+                alpha: GHOST_ALPHA,
+            });
+        }
+        self.views[view].render(&self.device, &self.queue, time, figures, &self.notes);
+    }
+
+    fn reload_shaders(&mut self) {
+        for view in &mut self.views {
+            view.renderpasses.reload_shaders(&self.device);
+        }
+        self.fog.reload_shaders(&self.device);
+    }
 }
+
 struct App {
+    scenario: Scenario,
+    shader_dir: PathBuf,
+    session: PathBuf,
+    /// without a player window, e.g. to prepare a scenario
+    gm_only: bool,
     state: Option<RenderState>,
-    dnd_state: DNDState,
-    dnd_start: Vector2<f32>,
+    modifiers: winit::keyboard::ModifiersState,
 }
 impl App {
-    fn new() -> Self {
+    fn new(scenario: Scenario, shader_dir: PathBuf, session: PathBuf, gm_only: bool) -> Self {
         Self {
+            scenario,
+            shader_dir,
+            session,
+            gm_only,
             state: None,
-            dnd_state: DNDState::Free,
-            dnd_start: Vector2 { x: 0., y: 0. },
+            modifiers: Default::default(),
         }
     }
+
+    fn init(&self, event_loop: &ActiveEventLoop) -> anyhow::Result<RenderState> {
+        let min_size = winit::dpi::Size::Logical(winit::dpi::LogicalSize::new(20., 20.));
+        let name = if self.scenario.name.is_empty() {
+            "atlas"
+        } else {
+            &self.scenario.name
+        };
+        let mut kinds = vec![ViewKind::Gamemaster];
+        if !self.gm_only {
+            kinds.push(ViewKind::Player);
+        }
+        let windows = kinds
+            .into_iter()
+            .map(|kind| {
+                let title = match kind {
+                    ViewKind::Gamemaster => format!("{name} (Spielleitung)"),
+                    ViewKind::Player => name.to_string(),
+                };
+                let attributes = Window::default_attributes()
+                    .with_min_inner_size(min_size)
+                    .with_title(title);
+                let window = event_loop
+                    .create_window(attributes)
+                    .context("could not open a window")?;
+                Ok((kind, Arc::new(window)))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let rt = tokio::runtime::Runtime::new()?;
+        let state = rt.block_on(RenderState::new(
+            windows,
+            &self.scenario,
+            &self.shader_dir,
+            self.session.clone(),
+        ))?;
+        for view in &state.views {
+            view.window.request_redraw();
+        }
+        Ok(state)
+    }
 }
 
 impl ApplicationHandler for App {
     fn resumed(&mut self, event_loop: &ActiveEventLoop) {
-        // Create window object
-        let min_size = winit::dpi::Size::Logical(winit::dpi::LogicalSize::new(20., 20.));
-
-        let window = Arc::new(
-            event_loop
-                .create_window(Window::default_attributes().with_min_inner_size(min_size))
-                .unwrap(),
-        );
-        let rt = tokio::runtime::Runtime::new().unwrap();
-        let state = rt.block_on(RenderState::new(window.clone()));
-        self.state = Some(state);
-
-        window.request_redraw();
+        if self.state.is_some() {
+            return;
+        }
+        match self.init(event_loop) {
+            Ok(state) => self.state = Some(state),
+            Err(e) => {
+                error!("{e:#}");
+                event_loop.exit();
+            }
+        }
     }
 
-    fn window_event(&mut self, event_loop: &ActiveEventLoop, _id: WindowId, event: WindowEvent) {
-        let app_state = self.state.as_mut().unwrap();
+    fn window_event(&mut self, event_loop: &ActiveEventLoop, id: WindowId, event: WindowEvent) {
+        let Some(app_state) = self.state.as_mut() else {
+            return;
+        };
+        let Some(idx) = app_state.view_index(id) else {
+            return;
+        };
         // reset zoom position
         match event {
             WindowEvent::CloseRequested => {
-                println!("The close button was pressed; stopping");
-                event_loop.exit();
+                if app_state.views[idx].kind == ViewKind::Gamemaster {
+                    println!("The close button was pressed; stopping");
+                    event_loop.exit();
+                } else {
+                    // dropping the window closes it
+                    app_state.views.remove(idx);
+                }
             }
             WindowEvent::RedrawRequested => {
-                app_state.render();
+                app_state.render(idx);
                 // Emits a new redraw requested event.
-                app_state.get_window().request_redraw();
+                app_state.views[idx].window.request_redraw();
             }
             WindowEvent::Resized(size) => {
                 // Reconfigures the size of the surface. We do not re-render
                 // here as this event is always followed up by redraw request.
-                app_state.resize(size);
+                app_state.views[idx].resize(&app_state.device, size);
             }
             WindowEvent::KeyboardInput {
                 event: KeyEvent {
@@ -244,35 +564,73 @@ impl ApplicationHandler for App {
                 ..
             } => {
                 if input == "r" {
-                    app_state.renderpasses.reload_shaders(&app_state.device)
+                    app_state.reload_shaders();
+                }
+            }
+            WindowEvent::ModifiersChanged(modifiers) => self.modifiers = modifiers.state(),
+            // ctrl + left click opens or closes a door
+            WindowEvent::MouseInput {
+                button: winit::event::MouseButton::Left,
+                state: winit::event::ElementState::Pressed,
+                ..
+            } if self.modifiers.control_key()
+                && app_state.views[idx].kind == ViewKind::Gamemaster =>
+            {
+                let pos = app_state.views[idx].metadata.mouse_map_pos();
+                app_state.toggle_door(pos);
+            }
+            // shift + left click uncovers a region for the players
+            WindowEvent::MouseInput {
+                button: winit::event::MouseButton::Left,
+                state: winit::event::ElementState::Pressed,
+                ..
+            } if self.modifiers.shift_key()
+                && app_state.views[idx].kind == ViewKind::Gamemaster =>
+            {
+                let pos = app_state.views[idx].metadata.mouse_map_pos();
+                if let Some(name) = app_state.regions.at(pos).map(|r| r.name.clone())
+                    && app_state.regions.reveal(&name)
+                {
+                    app_state.fog_dirty = true;
                 }
             }
             WindowEvent::MouseInput { button, state, .. } => {
-                // right click means drag the map
+                // right click means drag the map, left click drags a figure, with alt off the grid
+                let pos = app_state.views[idx].metadata.mouse_map_pos();
+                let hit = app_state.figure_at(app_state.views[idx].kind, pos);
+                let target = app_state.drop_position(pos, self.modifiers.alt_key());
+                let view = &mut app_state.views[idx];
 
                 match (button, state) {
                     (winit::event::MouseButton::Right, winit::event::ElementState::Pressed) => {
-                        if self.dnd_state == DNDState::Free {
-                            self.dnd_state = DNDState::Right;
-                            self.dnd_start = app_state.metadata.mouse_pos;
+                        if view.dnd_state == DNDState::Free {
+                            view.dnd_state = DNDState::Right;
+                            view.dnd_start = view.metadata.mouse_pos;
                         }
                     }
                     (winit::event::MouseButton::Right, winit::event::ElementState::Released) => {
-                        if self.dnd_state == DNDState::Right {
-                            self.dnd_state = DNDState::Free;
-                            app_state.metadata.dnd_map_movacc = app_state.metadata.map_translation;
+                        if view.dnd_state == DNDState::Right {
+                            view.dnd_state = DNDState::Free;
+                            view.metadata.dnd_map_movacc = view.metadata.map_translation;
                         }
                     }
                     (winit::event::MouseButton::Left, winit::event::ElementState::Pressed) => {
-                        if self.dnd_state == DNDState::Free {
-                            self.dnd_state = DNDState::Left;
-                            self.dnd_start = app_state.metadata.mouse_pos;
+                        if view.dnd_state == DNDState::Free {
+                            view.dnd_state = DNDState::Left;
+                            view.dnd_start = view.metadata.mouse_pos;
+                            view.dragged = hit;
+                            view.ghost = target;
                         }
                     }
                     (winit::event::MouseButton::Left, winit::event::ElementState::Released) => {
-                        if self.dnd_state == DNDState::Left {
-                            self.dnd_state = DNDState::Free;
-                            self.dnd_start = app_state.metadata.mouse_pos;
+                        if view.dnd_state == DNDState::Left {
+                            view.dnd_state = DNDState::Free;
+                            view.dnd_start = view.metadata.mouse_pos;
+                            if let Some(figure) = view.dragged.take() {
+                                app_state.figures[figure].position = target;
+                                // new sight, maybe a new region
+                                app_state.fog_dirty = true;
+                            }
                         }
                     }
                     _ => {}
@@ -280,35 +638,39 @@ impl ApplicationHandler for App {
             }
 
             WindowEvent::CursorMoved { position, .. } => {
-                app_state.metadata.mouse_pos = cgmath::Vector2 {
+                let free = self.modifiers.alt_key();
+                let view = &mut app_state.views[idx];
+                view.metadata.mouse_pos = cgmath::Vector2 {
                     x: position.x as f32,
-                    y: app_state.metadata.window_size.y - position.y as f32,
+                    y: view.metadata.window_size.y - position.y as f32,
                 };
-                if self.dnd_state == DNDState::Right {
-                    app_state.metadata.map_translation = app_state.metadata.dnd_map_movacc
+                if view.dnd_state == DNDState::Right {
+                    view.metadata.map_translation = view.metadata.dnd_map_movacc
                         + Vector2 {
-                            x: self.dnd_start.x - app_state.metadata.mouse_pos.x,
-                            y: app_state.metadata.mouse_pos.y - self.dnd_start.y,
+                            x: view.dnd_start.x - view.metadata.mouse_pos.x,
+                            y: view.metadata.mouse_pos.y - view.dnd_start.y,
                         };
                 }
-                if self.dnd_state == DNDState::Left {
-                    //TODO
+                if view.dnd_state == DNDState::Left && view.dragged.is_some() {
+                    let pos = view.metadata.mouse_map_pos();
+                    view.ghost = if free { pos } else { app_state.grid.snap(pos) };
                 }
             }
             WindowEvent::MouseWheel { delta, .. } => {
+                let view = &mut app_state.views[idx];
                 let delta = match delta {
                     winit::event::MouseScrollDelta::LineDelta(_, dy) => dy,
                     winit::event::MouseScrollDelta::PixelDelta(pos) => pos.y as f32,
                 };
-                let old_zoom = app_state.metadata.map_zoom;
-                app_state.metadata.map_zoom = f32::clamp(old_zoom * (1. + delta / 4.), 0.1, 10.);
+                let old_zoom = view.metadata.map_zoom;
+                view.metadata.map_zoom = f32::clamp(old_zoom * (1. + delta / 4.), 0.1, 10.);
             }
             _ => (),
         }
     }
 }
 
-fn main() {
+fn main() -> anyhow::Result<()> {
     // wgpu uses `log` for all of our logging, so we initialize a logger with the `env_logger` crate.
     //
     // To change the log level, set the `RUST_LOG` environment variable. See the `env_logger`
@@ -321,7 +683,10 @@ fn main() {
         .with(tracing_subscriber::fmt::layer())
         .init();
 
-    let event_loop = EventLoop::new().unwrap();
+    let args = Args::parse();
+    let scenario = Scenario::load(&args.scenario)?;
+
+    let event_loop = EventLoop::new()?;
 
     // When the current loop iteration finishes, immediately begin a new
     // iteration regardless of whether or not new events are available to
@@ -335,6 +700,8 @@ fn main() {
     // the background.
     // event_loop.set_control_flow(ControlFlow::Wait);
 
-    let mut app = App::new();
-    event_loop.run_app(&mut app).unwrap();
+    let session = Session::path(&args.scenario);
+    let mut app = App::new(scenario, args.shaders, session, args.gm_only);
+    event_loop.run_app(&mut app)?;
+    Ok(())
 }
@@ -1,10 +1,16 @@
-use crate::{renderpass::ColorRenderPass, uniform::UniformBuffer};
+use std::path::{Path, PathBuf};
+
+use crate::{
+    fog::FogOfWar,
+    renderpass::{ColorRenderPass, load_shader},
+    uniform::UniformBuffer,
+};
 use bytemuck::{Pod, Zeroable};
 use cgmath::Vector2;
+use tracing::error;
 use wgpu::{MultisampleState, RenderPipeline};
 
-const SHADER_NAME: &str = "shaders/render_map.wgsl";
-const IMAGE_NAME: &str = "images/Unterbaucheingeweide 01 (73 x 65).png";
+const SHADER_NAME: &str = "render_map.wgsl";
 
 #[repr(C)]
 #[derive(Debug, Clone, Copy, Pod, Zeroable)]
@@ -16,6 +22,33 @@ pub struct Metadata {
     pub mouse_pos: Vector2<f32>,
     // movement accumulator (=sum of previous movements)
     pub dnd_map_movacc: Vector2<f32>,
+    /// 1 hides everything unexplored, the GM looks through it
+    pub fog_opacity: f32,
+    pub _padding: f32,
+}
+
+impl Metadata {
+    /// the map pixel at a window pixel (y pointing down), the transform of the map shader
+    pub fn screen_to_map(&self, screen: Vector2<f32>) -> [f32; 2] {
+        let center = self.window_size / 2.;
+        let map = (screen + self.map_translation - center) * self.map_zoom + center;
+        [map.x, map.y]
+    }
+
+    /// where a map pixel is in the window, y pointing down
+    pub fn map_to_screen(&self, [x, y]: [f32; 2]) -> Vector2<f32> {
+        let center = self.window_size / 2.;
+        (Vector2::new(x, y) - center) / self.map_zoom + center - self.map_translation
+    }
+
+    /// the map pixel under the mouse
+    pub fn mouse_map_pos(&self) -> [f32; 2] {
+        // mouse_pos is counted from the bottom of the window
+        self.screen_to_map(Vector2::new(
+            self.mouse_pos.x,
+            self.window_size.y - self.mouse_pos.y,
+        ))
+    }
 }
 
 struct SamplerData {
@@ -23,14 +56,8 @@ struct SamplerData {
 }
 
 impl SamplerData {
-    fn new(device: &wgpu::Device, queue: &wgpu::Queue) -> Self {
+    fn new(device: &wgpu::Device, queue: &wgpu::Queue, image: &image::RgbaImage) -> Self {
         // For more texture loading see: https://sotrh.github.io/learn-wgpu/beginner/tutorial5-textures/#getting-data-into-a-texture
-        let image: image::RgbaImage = image::load(
-            std::io::BufReader::new(std::fs::File::open(IMAGE_NAME).unwrap()),
-            image::ImageFormat::Png,
-        )
-        .unwrap()
-        .into();
         let (w, h) = image.dimensions();
         let texture = device.create_texture(&wgpu::TextureDescriptor {
             format: wgpu::TextureFormat::Rgba8UnormSrgb,
@@ -53,7 +80,7 @@ impl SamplerData {
                 aspect: wgpu::TextureAspect::All,
                 origin: wgpu::Origin3d::ZERO,
             },
-            image.into_raw().as_slice(),
+            image.as_raw().as_slice(),
             wgpu::TexelCopyBufferLayout {
                 offset: 0,
                 bytes_per_row: Some(w * 4),
@@ -122,7 +149,9 @@ pub struct MapRenderpass {
     pub pipeline: RenderPipeline,
     uniforms: Option<UniformBuffer<Metadata>>,
     sampler_data: SamplerData,
+    fog_bind_group: wgpu::BindGroup,
     color_format: wgpu::TextureFormat,
+    shader_dir: PathBuf,
 }
 
 impl MapRenderpass {
@@ -130,45 +159,57 @@ impl MapRenderpass {
         color_format: wgpu::TextureFormat,
         device: &wgpu::Device,
         queue: &wgpu::Queue,
-    ) -> Self {
-        let sampler_data = SamplerData::new(device, queue);
-        Self {
-            pipeline: Self::create_pipeline(device, color_format),
+        map: &image::RgbaImage,
+        fog: &FogOfWar,
+        shader_dir: &Path,
+    ) -> anyhow::Result<Self> {
+        let sampler_data = SamplerData::new(device, queue, map);
+        Ok(Self {
+            pipeline: Self::create_pipeline(device, color_format, shader_dir)?,
             uniforms: None,
             sampler_data,
+            fog_bind_group: fog.bind_group().clone(),
             color_format,
-        }
+            shader_dir: shader_dir.to_path_buf(),
+        })
     }
 
     pub fn get_new_bind_groups(
         &mut self,
         uniforms: Metadata,
         device: &wgpu::Device,
-    ) -> [&wgpu::BindGroup; 2] {
+    ) -> [&wgpu::BindGroup; 3] {
         self.uniforms = Some(UniformBuffer::new(device, uniforms, None));
         [
             self.uniforms.as_ref().unwrap().bind_group(),
             &self.sampler_data.bind_group,
+            &self.fog_bind_group,
         ]
     }
+    /// keeps the old pipeline if the shader cannot be read
     pub fn reload_shaders(&mut self, device: &wgpu::Device) {
-        self.pipeline = Self::create_pipeline(device, self.color_format);
+        match Self::create_pipeline(device, self.color_format, &self.shader_dir) {
+            Ok(pipeline) => self.pipeline = pipeline,
+            Err(e) => error!("{e:#}"),
+        }
     }
 }
 
 impl ColorRenderPass<Metadata> for MapRenderpass {
-    fn create_pipeline(device: &wgpu::Device, color_format: wgpu::TextureFormat) -> RenderPipeline {
+    fn create_pipeline(
+        device: &wgpu::Device,
+        color_format: wgpu::TextureFormat,
+        shader_dir: &Path,
+    ) -> anyhow::Result<RenderPipeline> {
         let layout = wgpu::PipelineLayoutDescriptor {
             bind_group_layouts: &[
                 &UniformBuffer::<Metadata>::bind_group_layout(device),
                 &SamplerData::bglayout(device),
+                &FogOfWar::bglayout(device),
             ],
             ..Default::default()
         };
-        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
-            source: wgpu::ShaderSource::Wgsl(std::fs::read_to_string(SHADER_NAME).unwrap().into()),
-            label: None,
-        });
+        let shader = load_shader(device, shader_dir, SHADER_NAME)?;
         let rpl_desc = wgpu::RenderPipelineDescriptor {
             layout: Some(&device.create_pipeline_layout(&layout)),
             vertex: wgpu::VertexState {
@@ -194,7 +235,7 @@ impl ColorRenderPass<Metadata> for MapRenderpass {
             label: None,
             multiview: None,
         };
-        device.create_render_pipeline(&rpl_desc)
+        Ok(device.create_render_pipeline(&rpl_desc))
     }
 
     fn render(
@@ -225,6 +266,6 @@ impl ColorRenderPass<Metadata> for MapRenderpass {
             pass.set_bind_group(idx as u32, bg, &[]);
         }
         pass.set_pipeline(&self.pipeline);
-        pass.draw(0..4, 0..1);
+        pass.draw(0..3, 0..1);
     }
 }
@@ -0,0 +1,97 @@
+//! The GM's notes, drawn with egui as labels that stick to their place on the map.
+
+use std::sync::Arc;
+
+use winit::window::Window;
+
+use crate::{maprender::Metadata, scenario::Note};
+
+pub struct NoteOverlay {
+    state: egui_winit::State,
+    renderer: egui_wgpu::Renderer,
+}
+
+impl NoteOverlay {
+    pub fn new(
+        device: &wgpu::Device,
+        color_format: wgpu::TextureFormat,
+        window: &Arc<Window>,
+    ) -> Self {
+        let state = egui_winit::State::new(
+            egui::Context::default(),
+            egui::ViewportId::ROOT,
+            window,
+            Some(window.scale_factor() as f32),
+            None,
+            Some(device.limits().max_texture_dimension_2d as usize),
+        );
+        let renderer =
+            egui_wgpu::Renderer::new(device, color_format, egui_wgpu::RendererOptions::default());
+        Self { state, renderer }
+    }
+
+    /// draws `notes` on top of whatever is in `target`
+    #[allow(clippy::too_many_arguments)]
+    pub fn render(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        encoder: &mut wgpu::CommandEncoder,
+        target: &wgpu::TextureView,
+        window: &Window,
+        metadata: &Metadata,
+        notes: &[Note],
+    ) {
+        let input = self.state.take_egui_input(window);
+        let ctx = self.state.egui_ctx().clone();
+        let output = ctx.run(input, |ctx| {
+            let pixels_per_point = ctx.pixels_per_point();
+            for (i, note) in notes.iter().enumerate() {
+                let pos = metadata.map_to_screen(note.position) / pixels_per_point;
+                egui::Area::new(egui::Id::new(("note", i)))
+                    .fixed_pos(egui::pos2(pos.x, pos.y))
+                    .interactable(false)
+                    .show(ctx, |ui| {
+                        egui::Frame::popup(ui.style()).show(ui, |ui| ui.label(&note.text));
+                    });
+            }
+        });
+        self.state
+            .handle_platform_output(window, output.platform_output);
+
+        let jobs = ctx.tessellate(output.shapes, output.pixels_per_point);
+        let size = window.inner_size();
+        let screen = egui_wgpu::ScreenDescriptor {
+            size_in_pixels: [size.width, size.height],
+            pixels_per_point: output.pixels_per_point,
+        };
+        for (id, delta) in &output.textures_delta.set {
+            self.renderer.update_texture(device, queue, *id, delta);
+        }
+        // only paint callbacks produce extra command buffers, notes have none
+        self.renderer
+            .update_buffers(device, queue, encoder, &jobs, &screen);
+        let mut pass = encoder
+            .begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Notes Render Pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    depth_slice: None,
+                    ops: wgpu::Operations {
+                        store: wgpu::StoreOp::Store,
+                        load: wgpu::LoadOp::Load,
+                    },
+                    resolve_target: None,
+                    view: target,
+                })],
+                depth_stencil_attachment: None,
+                occlusion_query_set: None,
+                timestamp_writes: None,
+            })
+            .forget_lifetime();
+        self.renderer.render(&mut pass, &jobs, &screen);
+        drop(pass);
+        for id in &output.textures_delta.free {
+            self.renderer.free_texture(id);
+        }
+    }
+}
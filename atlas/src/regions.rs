@@ -0,0 +1,207 @@
+//! Rooms that are revealed all at once, when a figure steps in or the GM uncovers them.
+//! Which ones are revealed is kept in a session file next to the scenario.
+
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use tracing::{error, info};
+
+use crate::{
+    fog::Reveal,
+    scenario::Region,
+    visibility::{cross, sub},
+};
+
+/// what has happened so far in a game
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Session {
+    #[serde(default)]
+    pub revealed: BTreeSet<String>,
+}
+
+impl Session {
+    /// `keller.toml` keeps its session in `keller.session.toml`
+    pub fn path(scenario: &Path) -> PathBuf {
+        scenario.with_extension("session.toml")
+    }
+
+    /// a missing file is a new session
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("could not read session {}", path.display()))?;
+        toml::from_str(&text).with_context(|| format!("invalid session {}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        std::fs::write(path, toml::to_string(self)?)
+            .with_context(|| format!("could not write session {}", path.display()))
+    }
+}
+
+/// even-odd rule, works for any simple polygon
+pub fn contains(polygon: &[[f32; 2]], p: [f32; 2]) -> bool {
+    let mut inside = false;
+    for i in 0..polygon.len() {
+        let (a, b) = (polygon[i], polygon[(i + 1) % polygon.len()]);
+        if (a[1] > p[1]) != (b[1] > p[1])
+            && p[0] < a[0] + (p[1] - a[1]) / (b[1] - a[1]) * (b[0] - a[0])
+        {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+fn in_triangle(p: [f32; 2], a: [f32; 2], b: [f32; 2], c: [f32; 2]) -> bool {
+    cross(sub(b, a), sub(p, a)) >= 0.
+        && cross(sub(c, b), sub(p, b)) >= 0.
+        && cross(sub(a, c), sub(p, c)) >= 0.
+}
+
+/// Ear clipping, a triangle list for a simple polygon of either orientation.
+pub fn triangulate(polygon: &[[f32; 2]]) -> Vec<[f32; 2]> {
+    let mut left: Vec<[f32; 2]> = polygon.to_vec();
+    let area: f32 = (0..left.len())
+        .map(|i| cross(left[i], left[(i + 1) % left.len()]))
+        .sum();
+    if area < 0. {
+        left.reverse();
+    }
+    let mut triangles = vec![];
+    while left.len() > 3 {
+        let n = left.len();
+        let ear = (0..n).find(|&i| {
+            let (a, b, c) = (left[(i + n - 1) % n], left[i], left[(i + 1) % n]);
+            cross(sub(b, a), sub(c, b)) > 0.
+                && !left
+                    .iter()
+                    .any(|&p| p != a && p != b && p != c && in_triangle(p, a, b, c))
+        });
+        // self intersecting polygons have no ears left at some point, what remains is fanned
+        let Some(i) = ear else { break };
+        triangles.extend([left[(i + n - 1) % n], left[i], left[(i + 1) % n]]);
+        left.remove(i);
+    }
+    for i in 1..left.len().saturating_sub(1) {
+        triangles.extend([left[0], left[i], left[i + 1]]);
+    }
+    triangles
+}
+
+pub struct Regions {
+    regions: Vec<Region>,
+    triangles: Vec<Vec<[f32; 2]>>,
+    session: Session,
+    session_path: PathBuf,
+}
+
+impl Regions {
+    /// regions `revealed` in the scenario start revealed, as do those from the session
+    pub fn new(regions: &[Region], session_path: PathBuf) -> anyhow::Result<Self> {
+        let mut session = Session::load(&session_path)?;
+        session.revealed.extend(
+            regions
+                .iter()
+                .filter(|region| region.revealed)
+                .map(|region| region.name.clone()),
+        );
+        Ok(Self {
+            regions: regions.to_vec(),
+            triangles: regions.iter().map(|r| triangulate(&r.polygon)).collect(),
+            session,
+            session_path,
+        })
+    }
+
+    pub fn at(&self, p: [f32; 2]) -> Option<&Region> {
+        self.regions.iter().find(|r| contains(&r.polygon, p))
+    }
+
+    pub fn is_revealed(&self, region: &Region) -> bool {
+        self.session.revealed.contains(&region.name)
+    }
+
+    /// reveals the region named `name`, true if it was not revealed before
+    pub fn reveal(&mut self, name: &str) -> bool {
+        if !self.session.revealed.insert(name.to_string()) {
+            return false;
+        }
+        info!("region {name:?} revealed");
+        if let Err(e) = self.session.save(&self.session_path) {
+            error!("{e:#}");
+        }
+        true
+    }
+
+    /// reveals the regions the figures at `positions` stand in, true if there were new ones
+    pub fn enter(&mut self, positions: &[[f32; 2]]) -> bool {
+        let entered: Vec<String> = self
+            .regions
+            .iter()
+            .filter(|r| positions.iter().any(|&p| contains(&r.polygon, p)))
+            .map(|r| r.name.clone())
+            .collect();
+        let mut changed = false;
+        for name in entered {
+            changed |= self.reveal(&name);
+        }
+        changed
+    }
+
+    /// revealed regions for the fog, the ones with a figure in them are visible
+    pub fn reveals(&self, positions: &[[f32; 2]]) -> Vec<Reveal> {
+        self.regions
+            .iter()
+            .zip(&self.triangles)
+            .filter(|(region, _)| self.is_revealed(region))
+            .map(|(region, triangles)| Reveal {
+                triangles: triangles.clone(),
+                visible: positions.iter().any(|&p| contains(&region.polygon, p)),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{contains, triangulate};
+    use crate::visibility::{cross, sub};
+
+    #[test]
+    fn triangulates_rooms() {
+        // an L shaped room, clockwise on screen
+        let room = [
+            [0., 0.],
+            [20., 0.],
+            [20., 10.],
+            [10., 10.],
+            [10., 20.],
+            [0., 20.],
+        ];
+        assert!(contains(&room, [5., 15.]));
+        assert!(!contains(&room, [15., 15.]));
+
+        let triangles = triangulate(&room);
+        assert_eq!(triangles.len(), (room.len() - 2) * 3);
+        let area: f32 = triangles
+            .chunks(3)
+            .map(|t| cross(sub(t[1], t[0]), sub(t[2], t[0])).abs() / 2.)
+            .sum();
+        assert_eq!(area, 300.);
+        // no triangle covers the missing corner
+        for t in triangles.chunks(3) {
+            assert!(!contains(t, [15., 15.]));
+        }
+
+        let mut reversed = room;
+        reversed.reverse();
+        assert_eq!(triangulate(&reversed).len(), triangles.len());
+    }
+}
@@ -1,3 +1,6 @@
+use std::path::Path;
+
+use anyhow::Context;
 use bytemuck::{NoUninit, Pod};
 
 /// Updated External Data (UED):
@@ -7,7 +10,8 @@ pub trait ColorRenderPass<UED: NoUninit + Pod> {
     fn create_pipeline(
         device: &wgpu::Device,
         color_format: wgpu::TextureFormat,
-    ) -> wgpu::RenderPipeline;
+        shader_dir: &Path,
+    ) -> anyhow::Result<wgpu::RenderPipeline>;
     fn render(
         &mut self,
         device: &wgpu::Device,
@@ -17,3 +21,18 @@ pub trait ColorRenderPass<UED: NoUninit + Pod> {
         ued: UED,
     );
 }
+
+/// reads `name` from `shader_dir`, so shaders can be edited while atlas is running
+pub fn load_shader(
+    device: &wgpu::Device,
+    shader_dir: &Path,
+    name: &str,
+) -> anyhow::Result<wgpu::ShaderModule> {
+    let path = shader_dir.join(name);
+    let source = std::fs::read_to_string(&path)
+        .with_context(|| format!("could not read shader {}", path.display()))?;
+    Ok(device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        source: wgpu::ShaderSource::Wgsl(source.into()),
+        label: Some(name),
+    }))
+}
@@ -0,0 +1,374 @@
+//! The game configuration: which map, which figures, what the GM knows.
+//! Map coordinates are map pixels with the origin top left and y pointing down, the same
+//! as in the region files the dungeon generator exports.
+
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, bail};
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Scenario {
+    #[serde(default)]
+    pub name: String,
+    /// drawn in order, the first one is the base map and decides the map size
+    #[serde(rename = "layer")]
+    pub layers: Vec<Layer>,
+    #[serde(default)]
+    pub grid: Grid,
+    #[serde(default, rename = "figure")]
+    pub figures: Vec<FigureConfig>,
+    #[serde(default, rename = "region")]
+    pub regions: Vec<Region>,
+    /// what blocks the sight of the figures
+    #[serde(default, rename = "wall")]
+    pub walls: Vec<Wall>,
+    /// region files as written by the dungeon generator, merged into `regions` and `walls`
+    #[serde(default)]
+    pub region_files: Vec<PathBuf>,
+    #[serde(default, rename = "note")]
+    pub notes: Vec<Note>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Layer {
+    pub image: PathBuf,
+    /// where the top left corner of the layer goes on the base map
+    #[serde(default)]
+    pub offset: [i64; 2],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GridKind {
+    #[default]
+    Square,
+    /// pointy top hexes
+    Hex,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct Grid {
+    #[serde(default)]
+    pub kind: GridKind,
+    /// map pixels per field, the width of a square or the distance between hex centers
+    pub size: f32,
+    /// position of the first field's corner on the map
+    #[serde(default)]
+    pub offset: [f32; 2],
+    /// Schritt per field
+    #[serde(default = "one")]
+    pub schritt: f32,
+    /// figures dropped after dragging land in the middle of a field
+    #[serde(default = "yes")]
+    pub snap: bool,
+}
+
+fn one() -> f32 {
+    1.
+}
+
+fn yes() -> bool {
+    true
+}
+
+impl Default for Grid {
+    fn default() -> Self {
+        Self {
+            kind: GridKind::Square,
+            size: 32.,
+            offset: [0., 0.],
+            schritt: 1.,
+            snap: true,
+        }
+    }
+}
+
+/// the nearest hex in axial coordinates, by way of cube coordinates
+fn hex_round(q: f32, r: f32) -> (f32, f32) {
+    let s = -q - r;
+    let (mut rq, mut rr, rs) = (q.round(), r.round(), s.round());
+    let (dq, dr, ds) = ((rq - q).abs(), (rr - r).abs(), (rs - s).abs());
+    if dq > dr && dq > ds {
+        rq = -rr - rs;
+    } else if dr > ds {
+        rr = -rq - rs;
+    }
+    (rq, rr)
+}
+
+impl Grid {
+    pub fn pixels_per_schritt(&self) -> f32 {
+        self.size / self.schritt
+    }
+
+    /// the middle of the field `p` is in, `p` itself if the grid does not snap
+    pub fn snap(&self, p: [f32; 2]) -> [f32; 2] {
+        if !self.snap {
+            return p;
+        }
+        let [x, y] = p;
+        let [ox, oy] = self.offset;
+        match self.kind {
+            GridKind::Square => {
+                let middle =
+                    |v: f32, o: f32| o + ((v - o) / self.size).floor() * self.size + self.size / 2.;
+                [middle(x, ox), middle(y, oy)]
+            }
+            GridKind::Hex => {
+                // the first hex touches the offset with its left side and its top
+                let radius = self.size / 3f32.sqrt();
+                let (cx, cy) = (ox + self.size / 2., oy + radius);
+                let r = (y - cy) / (1.5 * radius);
+                let q = (x - cx) / self.size - r / 2.;
+                let (q, r) = hex_round(q, r);
+                [cx + self.size * (q + r / 2.), cy + 1.5 * radius * r]
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct FigureConfig {
+    pub name: String,
+    /// the token, without one the figure is drawn as a disc
+    pub image: Option<PathBuf>,
+    pub position: [f32; 2],
+    /// fields the token covers in each direction, 2 for a troll
+    #[serde(default = "one")]
+    pub size: f32,
+    /// how far the figure sees in Schritt
+    #[serde(default = "default_sight")]
+    pub sight: f32,
+    /// only the GM sees it, e.g. a monster lying in wait
+    #[serde(default)]
+    pub hidden: bool,
+}
+
+fn default_sight() -> f32 {
+    10.
+}
+
+/// A named area of the map, given as a polygon in map pixels.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Region {
+    pub name: String,
+    pub polygon: Vec<[f32; 2]>,
+    pub notes: Option<String>,
+    /// uncovered from the start, e.g. where the party enters
+    #[serde(default)]
+    pub revealed: bool,
+}
+
+/// A wall as a line through `points` in map pixels, close it by repeating the first point.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Wall {
+    pub points: Vec<[f32; 2]>,
+    #[serde(default)]
+    pub door: bool,
+    /// only doors can be open
+    #[serde(default)]
+    pub open: bool,
+}
+
+impl Wall {
+    pub fn blocks(&self) -> bool {
+        !(self.door && self.open)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct RegionFile {
+    #[serde(default, rename = "region")]
+    regions: Vec<Region>,
+    #[serde(default, rename = "wall")]
+    walls: Vec<Wall>,
+}
+
+/// something only the GM sees
+#[derive(Debug, Clone, Deserialize)]
+pub struct Note {
+    pub position: [f32; 2],
+    pub text: String,
+}
+
+impl Region {
+    /// the average of the corners, good enough to put a label on a room
+    pub fn center(&self) -> [f32; 2] {
+        let n = self.polygon.len() as f32;
+        let [x, y] = self
+            .polygon
+            .iter()
+            .fold([0., 0.], |[x, y], p| [x + p[0], y + p[1]]);
+        [x / n, y / n]
+    }
+}
+
+impl Scenario {
+    /// the notes and those of the regions, placed in the middle of them
+    pub fn all_notes(&self) -> Vec<Note> {
+        let regions = self.regions.iter().filter_map(|region| {
+            region.notes.as_ref().map(|notes| Note {
+                position: region.center(),
+                text: format!("{}: {notes}", region.name),
+            })
+        });
+        self.notes.iter().cloned().chain(regions).collect()
+    }
+
+    /// Reads a scenario file. Relative paths in it are relative to the file.
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("could not read scenario {}", path.display()))?;
+        let mut scenario: Scenario = toml::from_str(&text)
+            .with_context(|| format!("invalid scenario {}", path.display()))?;
+        let dir = path.parent().unwrap_or(Path::new("."));
+        scenario.resolve(dir)?;
+        scenario.validate()?;
+        Ok(scenario)
+    }
+
+    fn resolve(&mut self, dir: &Path) -> anyhow::Result<()> {
+        for layer in &mut self.layers {
+            layer.image = dir.join(&layer.image);
+        }
+        for figure in &mut self.figures {
+            if let Some(image) = &mut figure.image {
+                *image = dir.join(&*image);
+            }
+        }
+        for file in &mut self.region_files {
+            *file = dir.join(&*file);
+            let text = std::fs::read_to_string(&*file)
+                .with_context(|| format!("could not read region file {}", file.display()))?;
+            let regions: RegionFile = toml::from_str(&text)
+                .with_context(|| format!("invalid region file {}", file.display()))?;
+            self.regions.extend(regions.regions);
+            self.walls.extend(regions.walls);
+        }
+        Ok(())
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        if self.layers.is_empty() {
+            bail!("the scenario needs at least one [[layer]] with the map");
+        }
+        if self.grid.size <= 0. || self.grid.schritt <= 0. {
+            bail!("grid size and schritt have to be positive");
+        }
+        if let Some(region) = self.regions.iter().find(|r| r.polygon.len() < 3) {
+            bail!("region {:?} needs at least three points", region.name);
+        }
+        // the session remembers revealed regions by name
+        let mut names = HashSet::new();
+        if let Some(region) = self.regions.iter().find(|r| !names.insert(&r.name)) {
+            bail!(
+                "region {:?} exists twice, rename it in one of the region files",
+                region.name
+            );
+        }
+        if self.walls.iter().any(|w| w.points.len() < 2) {
+            bail!("every wall needs at least two points");
+        }
+        Ok(())
+    }
+
+    /// all layers merged into one image, later layers on top
+    pub fn map_image(&self) -> anyhow::Result<image::RgbaImage> {
+        let mut layers = self.layers.iter().map(|layer| {
+            image::open(&layer.image)
+                .map(|img| (layer, img.into_rgba8()))
+                .with_context(|| format!("could not load map layer {}", layer.image.display()))
+        });
+        let (_, mut map) = layers.next().context("no map layer")??;
+        for layer in layers {
+            let (layer, img) = layer?;
+            image::imageops::overlay(&mut map, &img, layer.offset[0], layer.offset[1]);
+        }
+        Ok(map)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Grid, GridKind, Scenario};
+
+    #[test]
+    fn parses_scenario() {
+        let mut scenario: Scenario = toml::from_str(
+            r#"
+            name = "Keller"
+            region_files = []
+
+            [[layer]]
+            image = "keller.png"
+
+            [grid]
+            kind = "hex"
+            size = 48.0
+
+            [[figure]]
+            name = "Alrik"
+            position = [100.0, 120.0]
+
+            [[region]]
+            name = "Weinkeller"
+            polygon = [[0.0, 0.0], [64.0, 0.0], [64.0, 64.0]]
+
+            [[wall]]
+            points = [[64.0, 0.0], [64.0, 64.0]]
+            door = true
+            "#,
+        )
+        .unwrap();
+        scenario.resolve("scenarios".as_ref()).unwrap();
+        scenario.validate().unwrap();
+        assert_eq!(scenario.grid.kind, GridKind::Hex);
+        assert_eq!(scenario.grid.pixels_per_schritt(), 48.);
+        assert_eq!(scenario.figures[0].sight, 10.);
+        assert_eq!(scenario.figures[0].size, 1.);
+        assert!(!scenario.figures[0].hidden);
+        assert_eq!(
+            scenario.layers[0].image,
+            std::path::Path::new("scenarios/keller.png")
+        );
+
+        assert!(scenario.walls[0].blocks());
+
+        // two dungeon levels both have a "Raum 1"
+        scenario.regions.push(scenario.regions[0].clone());
+        assert!(scenario.validate().is_err());
+        scenario.regions.pop();
+
+        scenario.regions[0].polygon.pop();
+        assert!(scenario.validate().is_err());
+    }
+
+    #[test]
+    fn snaps_to_grid() {
+        let mut grid = Grid {
+            size: 10.,
+            ..Default::default()
+        };
+        assert_eq!(grid.snap([13., 27.]), [15., 25.]);
+        assert_eq!(grid.snap([-1., 0.]), [-5., 5.]);
+
+        grid.kind = GridKind::Hex;
+        let radius = 10. / 3f32.sqrt();
+        let close =
+            |a: [f32; 2], b: [f32; 2]| (a[0] - b[0]).abs() < 1e-3 && (a[1] - b[1]).abs() < 1e-3;
+        assert!(close(grid.snap([5., radius]), [5., radius]));
+        assert!(close(grid.snap([15.5, radius + 0.3]), [15., radius]));
+        // the second row is shifted by half a hex
+        let second = [10., 2.5 * radius];
+        assert!(close(grid.snap([10.4, second[1] - 0.2]), second));
+        assert!(close(grid.snap([13., second[1] + 1.]), second));
+
+        grid.snap = false;
+        assert_eq!(grid.snap([13., 27.]), [13., 27.]);
+    }
+}
@@ -0,0 +1,220 @@
+//! What a figure can see: the part of its sight circle that no wall is in the way of.
+//! Rays are cast to every wall corner (and a little to each side of it) and around the circle,
+//! the nearest hits sorted by angle give a polygon that is star shaped around the figure.
+
+use std::f32::consts::TAU;
+
+use crate::scenario::Wall;
+
+/// rays around the circle, in between the wall corners
+const CIRCLE_SAMPLES: usize = 64;
+/// how far the rays next to a corner are turned, to look past it
+const CORNER_OFFSET: f32 = 1e-4;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Segment {
+    pub a: [f32; 2],
+    pub b: [f32; 2],
+}
+
+pub(crate) fn sub(a: [f32; 2], b: [f32; 2]) -> [f32; 2] {
+    [a[0] - b[0], a[1] - b[1]]
+}
+
+pub(crate) fn cross(a: [f32; 2], b: [f32; 2]) -> f32 {
+    a[0] * b[1] - a[1] * b[0]
+}
+
+fn dot(a: [f32; 2], b: [f32; 2]) -> f32 {
+    a[0] * b[0] + a[1] * b[1]
+}
+
+/// all pieces of walls that block the sight, open doors do not
+pub fn segments(walls: &[Wall]) -> Vec<Segment> {
+    walls
+        .iter()
+        .filter(|wall| wall.blocks())
+        .flat_map(|wall| wall.points.windows(2).map(|w| Segment { a: w[0], b: w[1] }))
+        .collect()
+}
+
+impl Segment {
+    /// distance along the ray from `origin` in direction `dir` (unit length) to the segment
+    fn hit(&self, origin: [f32; 2], dir: [f32; 2]) -> Option<f32> {
+        let r = sub(self.b, self.a);
+        let denom = cross(dir, r);
+        if denom.abs() < f32::EPSILON {
+            return None;
+        }
+        let to_a = sub(self.a, origin);
+        let t = cross(to_a, r) / denom;
+        let u = cross(to_a, dir) / denom;
+        (t >= 0. && (0. ..=1.).contains(&u)).then_some(t)
+    }
+
+    /// angles from `origin` to where the segment crosses the circle
+    fn circle_crossings(&self, origin: [f32; 2], radius: f32) -> Vec<f32> {
+        let r = sub(self.b, self.a);
+        let f = sub(self.a, origin);
+        let (a, b, c) = (dot(r, r), 2. * dot(f, r), dot(f, f) - radius * radius);
+        let discriminant = b * b - 4. * a * c;
+        if a == 0. || discriminant < 0. {
+            return vec![];
+        }
+        let sqrt = discriminant.sqrt();
+        [(-b - sqrt) / (2. * a), (-b + sqrt) / (2. * a)]
+            .into_iter()
+            .filter(|u| (0. ..=1.).contains(u))
+            .map(|u| angle(origin, [self.a[0] + u * r[0], self.a[1] + u * r[1]]))
+            .collect()
+    }
+
+    /// the point of the segment nearest to `p`
+    fn closest(&self, p: [f32; 2]) -> [f32; 2] {
+        let r = sub(self.b, self.a);
+        let len = dot(r, r);
+        let u = if len > 0. {
+            (dot(sub(p, self.a), r) / len).clamp(0., 1.)
+        } else {
+            0.
+        };
+        [self.a[0] + u * r[0], self.a[1] + u * r[1]]
+    }
+
+    fn distance(&self, p: [f32; 2]) -> f32 {
+        let d = sub(self.closest(p), p);
+        dot(d, d).sqrt()
+    }
+
+    /// false if the segment is entirely outside the circle
+    fn hit_possible(&self, origin: [f32; 2], radius: f32) -> bool {
+        self.distance(origin) <= radius
+    }
+}
+
+/// index of the door nearest to `p`, if it is at most `max_distance` away
+pub fn door_at(walls: &[Wall], p: [f32; 2], max_distance: f32) -> Option<usize> {
+    walls
+        .iter()
+        .enumerate()
+        .filter(|(_, wall)| wall.door)
+        .map(|(i, wall)| {
+            let distance = wall
+                .points
+                .windows(2)
+                .map(|w| Segment { a: w[0], b: w[1] }.distance(p))
+                .fold(f32::INFINITY, f32::min);
+            (i, distance)
+        })
+        .filter(|&(_, distance)| distance <= max_distance)
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(i, _)| i)
+}
+
+fn angle(origin: [f32; 2], p: [f32; 2]) -> f32 {
+    let d = sub(p, origin);
+    d[1].atan2(d[0])
+}
+
+/// Outline of what can be seen from `origin` up to `radius`, ordered by angle.
+pub fn visibility_polygon(origin: [f32; 2], radius: f32, segments: &[Segment]) -> Vec<[f32; 2]> {
+    let near: Vec<&Segment> = segments
+        .iter()
+        .filter(|s| s.hit_possible(origin, radius))
+        .collect();
+    let mut angles: Vec<f32> = (0..CIRCLE_SAMPLES)
+        .map(|i| i as f32 / CIRCLE_SAMPLES as f32 * TAU)
+        .collect();
+    for segment in &near {
+        for corner in [segment.a, segment.b] {
+            let d = sub(corner, origin);
+            if dot(d, d) <= radius * radius {
+                let a = angle(origin, corner);
+                angles.extend([a - CORNER_OFFSET, a, a + CORNER_OFFSET]);
+            }
+        }
+        angles.extend(segment.circle_crossings(origin, radius));
+    }
+    let mut angles: Vec<f32> = angles.into_iter().map(|a| a.rem_euclid(TAU)).collect();
+    angles.sort_by(f32::total_cmp);
+    angles.dedup();
+    angles
+        .into_iter()
+        .map(|a| {
+            let dir = [a.cos(), a.sin()];
+            let t = near
+                .iter()
+                .filter_map(|s| s.hit(origin, dir))
+                .fold(radius, f32::min);
+            [origin[0] + dir[0] * t, origin[1] + dir[1] * t]
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Segment, door_at, segments, visibility_polygon};
+    use crate::scenario::Wall;
+
+    fn distance(a: [f32; 2], b: [f32; 2]) -> f32 {
+        ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2)).sqrt()
+    }
+
+    #[test]
+    fn walls_stop_the_sight() {
+        let origin = [0., 0.];
+        // without walls it is a circle
+        let open = visibility_polygon(origin, 10., &[]);
+        assert!(
+            open.iter()
+                .all(|p| (distance(origin, *p) - 10.).abs() < 1e-3)
+        );
+
+        // a wall right of the figure, from top to bottom
+        let wall = Segment {
+            a: [4., -20.],
+            b: [4., 20.],
+        };
+        let outline = visibility_polygon(origin, 10., &[wall]);
+        assert!(outline.iter().all(|p| p[0] <= 4. + 1e-3), "{outline:?}");
+        assert!(outline.iter().any(|p| p[0] < -9.9));
+        assert!(outline.iter().all(|p| distance(origin, *p) <= 10. + 1e-3));
+
+        // looking past the end of a short wall
+        let short = Segment {
+            a: [4., 0.],
+            b: [4., 20.],
+        };
+        let outline = visibility_polygon(origin, 10., &[short]);
+        assert!(
+            outline.iter().any(|p| p[0] > 9. && p[1] < 0.),
+            "{outline:?}"
+        );
+        assert!(outline.iter().all(|p| !(p[0] > 4. + 1e-3 && p[1] > 1e-3)));
+    }
+
+    #[test]
+    fn doors_open_and_close() {
+        let wall = |points: Vec<[f32; 2]>, door| Wall {
+            points,
+            door,
+            open: false,
+        };
+        let mut walls = vec![
+            wall(vec![[0., 0.], [10., 0.]], false),
+            wall(vec![[10., 0.], [14., 0.]], true),
+            wall(vec![[0., 20.], [4., 20.]], true),
+        ];
+        // plain walls are never picked, the nearest door is
+        assert_eq!(door_at(&walls, [9., 1.], 2.), Some(1));
+        assert_eq!(door_at(&walls, [5., 1.], 2.), None);
+        assert_eq!(door_at(&walls, [2., 19.], 2.), Some(2));
+
+        assert_eq!(segments(&walls).len(), 3);
+        walls[1].open = true;
+        assert_eq!(segments(&walls).len(), 2);
+        // through the open door
+        let outline = visibility_polygon([12., 5.], 10., &segments(&walls));
+        assert!(outline.iter().any(|p| p[1] < -4.), "{outline:?}");
+    }
+}
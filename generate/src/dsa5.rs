@@ -0,0 +1,402 @@
+//! Regelkram aus DSA5, den mehrere Generatoren brauchen.
+
+use std::fmt::Display;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Steigerungsfaktor eines Talents, einer Kampftechnik o.ä.
+/// E ist die Spalte für Eigenschaften.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Steigerungsfaktor {
+    A,
+    B,
+    C,
+    D,
+    E,
+}
+
+impl Steigerungsfaktor {
+    /// AP-Kosten pro Punkt bis einschließlich [`Self::threshold`]
+    pub fn base_cost(self) -> u32 {
+        match self {
+            Self::A => 1,
+            Self::B => 2,
+            Self::C => 3,
+            Self::D => 4,
+            Self::E => 15,
+        }
+    }
+
+    /// der letzte Wert, der noch den Grundpreis kostet
+    pub fn threshold(self) -> u8 {
+        match self {
+            Self::E => 14,
+            _ => 12,
+        }
+    }
+
+    /// AP-Kosten, um einen Wert von `fw - 1` auf `fw` zu steigern.
+    /// Über dem Schwellwert steigen die Kosten linear: bei A-D kostet FW 13 das doppelte, 14 das dreifache usw.
+    pub fn step_cost(self, fw: u8) -> u32 {
+        if fw == 0 {
+            0
+        } else if fw <= self.threshold() {
+            self.base_cost()
+        } else {
+            self.base_cost() * (fw - self.threshold() + 1) as u32
+        }
+    }
+
+    /// AP-Kosten, um von `from` auf `to` zu steigern. 0 falls `to <= from`.
+    pub fn cost(self, from: u8, to: u8) -> u32 {
+        (from.saturating_add(1)..=to)
+            .map(|fw| self.step_cost(fw))
+            .sum()
+    }
+}
+
+impl Display for Steigerungsfaktor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let c = match self {
+            Self::A => "A",
+            Self::B => "B",
+            Self::C => "C",
+            Self::D => "D",
+            Self::E => "E",
+        };
+        write!(f, "{c}")
+    }
+}
+
+/// Die acht Eigenschaften
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Eigenschaft {
+    MU,
+    KL,
+    IN,
+    CH,
+    FF,
+    GE,
+    KO,
+    KK,
+}
+
+impl Eigenschaft {
+    pub const ALL: [Eigenschaft; 8] = [
+        Self::MU,
+        Self::KL,
+        Self::IN,
+        Self::CH,
+        Self::FF,
+        Self::GE,
+        Self::KO,
+        Self::KK,
+    ];
+}
+
+impl Display for Eigenschaft {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+/// Erfahrungsgrad bei der Heldenerschaffung, bestimmt AP und Maximalwerte
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Erfahrungsgrad {
+    Unerfahren,
+    Durchschnittlich,
+    Erfahren,
+    Kompetent,
+    Meisterlich,
+    Brillant,
+    Legendaer,
+}
+
+impl Erfahrungsgrad {
+    pub const ALL: [Erfahrungsgrad; 7] = [
+        Self::Unerfahren,
+        Self::Durchschnittlich,
+        Self::Erfahren,
+        Self::Kompetent,
+        Self::Meisterlich,
+        Self::Brillant,
+        Self::Legendaer,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Unerfahren => "Unerfahren",
+            Self::Durchschnittlich => "Durchschnittlich",
+            Self::Erfahren => "Erfahren",
+            Self::Kompetent => "Kompetent",
+            Self::Meisterlich => "Meisterlich",
+            Self::Brillant => "Brillant",
+            Self::Legendaer => "Legendär",
+        }
+    }
+    /// AP zur Heldenerschaffung
+    pub fn ap(self) -> u32 {
+        [900, 1000, 1100, 1200, 1400, 1700, 2100][self as usize]
+    }
+    pub fn max_eigenschaft(self) -> u8 {
+        12 + self as u8
+    }
+    pub fn max_eigenschaften_summe(self) -> u32 {
+        [95, 98, 100, 102, 105, 109, 114][self as usize]
+    }
+    pub fn max_fw(self) -> u8 {
+        10 + self as u8
+    }
+    pub fn max_ktw(self) -> u8 {
+        8 + 2 * self as u8
+    }
+}
+
+impl Display for Erfahrungsgrad {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Nahkampftechniken
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Kampftechnik {
+    Dolche,
+    Fechtwaffen,
+    Hiebwaffen,
+    Raufen,
+    Schwerter,
+    Stangenwaffen,
+    Zweihandhiebwaffen,
+}
+
+impl Kampftechnik {
+    pub const ALL: [Kampftechnik; 7] = [
+        Self::Dolche,
+        Self::Fechtwaffen,
+        Self::Hiebwaffen,
+        Self::Raufen,
+        Self::Schwerter,
+        Self::Stangenwaffen,
+        Self::Zweihandhiebwaffen,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Dolche => "Dolche",
+            Self::Fechtwaffen => "Fechtwaffen",
+            Self::Hiebwaffen => "Hiebwaffen",
+            Self::Raufen => "Raufen",
+            Self::Schwerter => "Schwerter",
+            Self::Stangenwaffen => "Stangenwaffen",
+            Self::Zweihandhiebwaffen => "Zweihandhiebwaffen",
+        }
+    }
+    pub fn steigerungsfaktor(self) -> Steigerungsfaktor {
+        match self {
+            Self::Dolche | Self::Raufen => Steigerungsfaktor::B,
+            _ => Steigerungsfaktor::C,
+        }
+    }
+    /// Leiteigenschaften, die für den PA-Bonus in Frage kommen
+    pub fn leiteigenschaften(self) -> &'static [Eigenschaft] {
+        match self {
+            Self::Dolche | Self::Fechtwaffen => &[Eigenschaft::GE],
+            Self::Hiebwaffen | Self::Zweihandhiebwaffen => &[Eigenschaft::KK],
+            Self::Raufen | Self::Schwerter | Self::Stangenwaffen => {
+                &[Eigenschaft::GE, Eigenschaft::KK]
+            }
+        }
+    }
+}
+
+impl Display for Kampftechnik {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Bonus auf AT/PA etc. für Eigenschaftswerte über 8
+pub fn eigenschaft_bonus(wert: u8) -> i32 {
+    (wert as i32 - 8).max(0) / 3
+}
+
+/// Ergebnis einer Fertigkeitsprobe (3W20 gegen drei Eigenschaften)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Probe {
+    pub wuerfe: [u8; 3],
+    /// Qualitätsstufe 1-6, None if failed
+    pub qs: Option<u8>,
+    pub kritisch: bool,
+    pub patzer: bool,
+}
+
+/// Every roll above the (modified) attribute costs that many FP.
+/// Two 1s always succeed, two 20s always fail.
+pub fn probe(rng: &mut impl Rng, eigenschaften: [u8; 3], fw: u8, modifikator: i32) -> Probe {
+    let wuerfe: [u8; 3] = std::array::from_fn(|_| rng.random_range(1..=20));
+    let count = |augen: u8| wuerfe.iter().filter(|&&w| w == augen).count();
+    let kritisch = count(1) >= 2;
+    let patzer = count(20) >= 2;
+    let rest: i32 = fw as i32
+        - wuerfe
+            .iter()
+            .zip(eigenschaften)
+            .map(|(&w, e)| (w as i32 - (e as i32 + modifikator)).max(0))
+            .sum::<i32>();
+    let qs = if patzer || (rest < 0 && !kritisch) {
+        None
+    } else {
+        Some(((rest.max(1) + 2) / 3).min(6) as u8)
+    };
+    Probe {
+        wuerfe,
+        qs,
+        kritisch,
+        patzer,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Talentgruppe {
+    Koerper,
+    Gesellschaft,
+    Natur,
+    Wissen,
+    Handwerk,
+}
+
+macro_rules! talente {
+    ($($variant:ident => $name:literal, $gruppe:ident, $sf:ident;)*) => {
+        /// Die Talente aus dem DSA5 Regelwerk, in der Reihenfolge des Regelwerks
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+        pub enum Talent {
+            $($variant,)*
+        }
+        impl Talent {
+            pub const ALL: &'static [Talent] = &[$(Talent::$variant,)*];
+
+            pub fn name(self) -> &'static str {
+                match self {
+                    $(Talent::$variant => $name,)*
+                }
+            }
+            pub fn gruppe(self) -> Talentgruppe {
+                match self {
+                    $(Talent::$variant => Talentgruppe::$gruppe,)*
+                }
+            }
+            pub fn steigerungsfaktor(self) -> Steigerungsfaktor {
+                match self {
+                    $(Talent::$variant => Steigerungsfaktor::$sf,)*
+                }
+            }
+        }
+    };
+}
+
+talente! {
+    Fliegen => "Fliegen", Koerper, B;
+    Gaukeleien => "Gaukeleien", Koerper, A;
+    Klettern => "Klettern", Koerper, B;
+    Koerperbeherrschung => "Körperbeherrschung", Koerper, D;
+    Kraftakt => "Kraftakt", Koerper, B;
+    Reiten => "Reiten", Koerper, B;
+    Schwimmen => "Schwimmen", Koerper, B;
+    Selbstbeherrschung => "Selbstbeherrschung", Koerper, D;
+    Singen => "Singen", Koerper, A;
+    Sinnesschaerfe => "Sinnesschärfe", Koerper, D;
+    Tanzen => "Tanzen", Koerper, A;
+    Taschendiebstahl => "Taschendiebstahl", Koerper, B;
+    Verbergen => "Verbergen", Koerper, C;
+    Zechen => "Zechen", Koerper, A;
+
+    BekehrenUeberzeugen => "Bekehren & Überzeugen", Gesellschaft, B;
+    Betoeren => "Betören", Gesellschaft, B;
+    Einschuechtern => "Einschüchtern", Gesellschaft, B;
+    Etikette => "Etikette", Gesellschaft, B;
+    Gassenwissen => "Gassenwissen", Gesellschaft, C;
+    Menschenkenntnis => "Menschenkenntnis", Gesellschaft, C;
+    Ueberreden => "Überreden", Gesellschaft, C;
+    Verkleiden => "Verkleiden", Gesellschaft, B;
+    Willenskraft => "Willenskraft", Gesellschaft, D;
+
+    Faehrtensuchen => "Fährtensuchen", Natur, C;
+    Fesseln => "Fesseln", Natur, A;
+    FischenAngeln => "Fischen & Angeln", Natur, A;
+    Orientierung => "Orientierung", Natur, B;
+    Pflanzenkunde => "Pflanzenkunde", Natur, C;
+    Tierkunde => "Tierkunde", Natur, C;
+    Wildnisleben => "Wildnisleben", Natur, C;
+
+    BrettGluecksspiel => "Brett- & Glücksspiel", Wissen, A;
+    Geographie => "Geographie", Wissen, B;
+    Geschichtswissen => "Geschichtswissen", Wissen, B;
+    GoetterKulte => "Götter & Kulte", Wissen, B;
+    Kriegskunst => "Kriegskunst", Wissen, B;
+    Magiekunde => "Magiekunde", Wissen, C;
+    Mechanik => "Mechanik", Wissen, B;
+    Rechnen => "Rechnen", Wissen, A;
+    Rechtskunde => "Rechtskunde", Wissen, A;
+    SagenLegenden => "Sagen & Legenden", Wissen, B;
+    Sphaerenkunde => "Sphärenkunde", Wissen, B;
+    Sternkunde => "Sternkunde", Wissen, A;
+
+    Alchimie => "Alchimie", Handwerk, C;
+    BooteSchiffe => "Boote & Schiffe", Handwerk, B;
+    Fahrzeuge => "Fahrzeuge", Handwerk, A;
+    Handel => "Handel", Handwerk, B;
+    HeilkundeGift => "Heilkunde Gift", Handwerk, B;
+    HeilkundeKrankheiten => "Heilkunde Krankheiten", Handwerk, B;
+    HeilkundeSeele => "Heilkunde Seele", Handwerk, B;
+    HeilkundeWunden => "Heilkunde Wunden", Handwerk, D;
+    Holzbearbeitung => "Holzbearbeitung", Handwerk, B;
+    Lebensmittelbearbeitung => "Lebensmittelbearbeitung", Handwerk, A;
+    Lederbearbeitung => "Lederbearbeitung", Handwerk, B;
+    MalenZeichnen => "Malen & Zeichnen", Handwerk, A;
+    Metallbearbeitung => "Metallbearbeitung", Handwerk, C;
+    Musizieren => "Musizieren", Handwerk, A;
+    Schloesserknacken => "Schlösserknacken", Handwerk, C;
+    Steinbearbeitung => "Steinbearbeitung", Handwerk, A;
+    Stoffbearbeitung => "Stoffbearbeitung", Handwerk, A;
+}
+
+impl Display for Talent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::{SeedableRng, rngs::StdRng};
+
+    use super::{Steigerungsfaktor, probe};
+
+    #[test]
+    fn ap_costs() {
+        assert_eq!(Steigerungsfaktor::A.cost(0, 12), 12);
+        assert_eq!(Steigerungsfaktor::B.step_cost(13), 4);
+        assert_eq!(Steigerungsfaktor::D.step_cost(15), 16);
+        assert_eq!(Steigerungsfaktor::C.cost(11, 14), 3 + 6 + 9);
+        assert_eq!(Steigerungsfaktor::C.cost(14, 11), 0);
+        assert_eq!(Steigerungsfaktor::E.cost(8, 14), 90);
+        assert_eq!(Steigerungsfaktor::E.cost(14, 16), 30 + 45);
+    }
+
+    #[test]
+    fn probe_qs() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..1000 {
+            let p = probe(&mut rng, [20, 20, 20], 10, 0);
+            // nothing can go wrong but a double 20
+            assert_eq!(p.qs.is_none(), p.patzer);
+            if !p.patzer {
+                assert_eq!(p.qs, Some(4));
+            }
+            let p = probe(&mut rng, [1, 1, 1], 0, 0);
+            assert_eq!(p.qs.is_some(), p.kritisch || p.wuerfe == [1, 1, 1]);
+        }
+    }
+}
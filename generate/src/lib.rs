@@ -1,4 +1,6 @@
+pub mod dsa5;
+
 pub trait RPGenerator: Iterator + Sync + Send {
     type Seed;
     fn seed(&mut self, s: Self::Seed);
-}
\ No newline at end of file
+}
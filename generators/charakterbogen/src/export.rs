@@ -0,0 +1,214 @@
+use std::{
+    fmt::Display,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use gaeste::Geschlecht;
+use generate::dsa5::{Eigenschaft, Kampftechnik, Talentgruppe};
+use serde_json::{Map, Value, json};
+
+use crate::Held;
+
+/// the Optolith version whose hero format we write
+const OPTOLITH_VERSION: &str = "1.5.1";
+
+fn optolith_ct(k: Kampftechnik) -> &'static str {
+    match k {
+        Kampftechnik::Dolche => "CT_3",
+        Kampftechnik::Fechtwaffen => "CT_4",
+        Kampftechnik::Hiebwaffen => "CT_5",
+        Kampftechnik::Raufen => "CT_9",
+        Kampftechnik::Schwerter => "CT_12",
+        Kampftechnik::Stangenwaffen => "CT_13",
+        Kampftechnik::Zweihandhiebwaffen => "CT_15",
+    }
+}
+
+fn optolith_attr(e: Eigenschaft) -> String {
+    format!("ATTR_{}", e as usize + 1)
+}
+
+/// unix seconds to an ISO 8601 UTC timestamp
+fn iso8601(secs: u64) -> String {
+    let days = (secs / 86400) as i64;
+    let rem = secs % 86400;
+    // civil from days, see http://howardhinnant.github.io/date_algorithms.html
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    let y = yoe + era * 400 + (m <= 2) as i64;
+    format!(
+        "{y:04}-{m:02}-{d:02}T{:02}:{:02}:{:02}.000Z",
+        rem / 3600,
+        (rem % 3600) / 60,
+        rem % 60
+    )
+}
+
+impl Held {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("heroes are always serializable")
+    }
+
+    /// A hero file that can be imported into Optolith.
+    /// The profession is exported as a custom profession (`P_0`) since our packages are not Optolith's.
+    pub fn to_optolith(&self) -> String {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let attr_values: Vec<Value> = self
+            .eigenschaften
+            .iter()
+            .map(|(&e, &w)| json!({ "id": optolith_attr(e), "value": w }))
+            .collect();
+        let talents: Map<String, Value> = self
+            .talente
+            .iter()
+            .filter(|&(_, &fw)| fw > 0)
+            .map(|(&t, &fw)| (format!("TAL_{}", t as usize + 1), json!(fw)))
+            .collect();
+        let ct: Map<String, Value> = self
+            .kampftechniken
+            .iter()
+            .filter(|&(_, &ktw)| ktw > 6)
+            .map(|(&k, &ktw)| (optolith_ct(k).to_string(), json!(ktw)))
+            .collect();
+        let hero = json!({
+            "clientVersion": OPTOLITH_VERSION,
+            "dateCreated": iso8601(now),
+            "dateModified": iso8601(now),
+            "id": format!("H_{now}"),
+            "phase": 3,
+            "locale": "de-DE",
+            "name": self.name,
+            "ap": { "total": self.ap_budget() },
+            "el": format!("EL_{}", self.erfahrungsgrad as usize + 1),
+            "r": self.spezies.optolith_id(),
+            "c": self.kultur.optolith_id(),
+            "isCultureAreaKnowledgeSelected": false,
+            "p": "P_0",
+            "professionName": self.profession.name(),
+            "sex": match self.geschlecht {
+                Geschlecht::Maennlich => "m",
+                Geschlecht::Weiblich => "f",
+            },
+            "pers": {},
+            "attr": {
+                "values": attr_values,
+                "attributeAdjustmentSelected": self
+                    .freie_modifikation
+                    .map(optolith_attr)
+                    .unwrap_or_else(|| "ATTR_1".into()),
+                "ae": 0,
+                "kp": 0,
+                "lp": 0,
+                "permanentAE": { "lost": 0, "redeemed": 0 },
+                "permanentKP": { "lost": 0, "redeemed": 0 },
+                "permanentLP": { "lost": 0 },
+            },
+            "activatable": {},
+            "talents": talents,
+            "ct": ct,
+            "spells": {},
+            "cantrips": [],
+            "liturgies": {},
+            "blessings": [],
+            "belongings": {
+                "items": {},
+                "armorZones": {},
+                "purse": { "d": "0", "s": "0", "h": "0", "k": "0" },
+            },
+            "rules": {
+                "higherParadeValues": 0,
+                "attributeValueLimit": false,
+                "enableAllRuleBooks": false,
+                "enabledRuleBooks": [],
+                "enableLanguageSpecializations": false,
+            },
+            "pets": {},
+        });
+        serde_json::to_string_pretty(&hero).expect("json values are always serializable")
+    }
+}
+
+/// the text sheet
+impl Display for Held {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}", self.name)?;
+        writeln!(
+            f,
+            "{} / {} / {} ({})",
+            self.spezies, self.kultur, self.profession, self.erfahrungsgrad
+        )?;
+        writeln!(
+            f,
+            "AP: {} von {} ausgegeben",
+            self.ap_ausgegeben(),
+            self.ap_budget()
+        )?;
+        writeln!(f)?;
+        let eig: Vec<String> = Eigenschaft::ALL
+            .iter()
+            .map(|&e| format!("{e} {:>2}", self.eigenschaft(e)))
+            .collect();
+        writeln!(f, "{}", eig.join("  "))?;
+        write!(f, "LeP {}", self.lep())?;
+        if let Some(asp) = self.asp() {
+            write!(f, "  AsP {asp}")?;
+        }
+        if let Some(kap) = self.kap() {
+            write!(f, "  KaP {kap}")?;
+        }
+        writeln!(
+            f,
+            "  SK {}  ZK {}  AW {}  INI {}  GS {}",
+            self.sk(),
+            self.zk(),
+            self.aw(),
+            self.ini(),
+            self.spezies.gs()
+        )?;
+
+        for (gruppe, titel) in [
+            (Talentgruppe::Koerper, "Körper"),
+            (Talentgruppe::Gesellschaft, "Gesellschaft"),
+            (Talentgruppe::Natur, "Natur"),
+            (Talentgruppe::Wissen, "Wissen"),
+            (Talentgruppe::Handwerk, "Handwerk"),
+        ] {
+            let talente: Vec<String> = self
+                .talente
+                .iter()
+                .filter(|(t, _)| t.gruppe() == gruppe)
+                .map(|(t, fw)| format!("{t} {fw}"))
+                .collect();
+            if !talente.is_empty() {
+                writeln!(f, "{titel}: {}", talente.join(", "))?;
+            }
+        }
+        let kt: Vec<String> = self
+            .kampftechniken
+            .iter()
+            .map(|(k, ktw)| format!("{k} {ktw}"))
+            .collect();
+        writeln!(f, "Kampftechniken: {}", kt.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::iso8601;
+
+    #[test]
+    fn timestamps() {
+        assert_eq!(iso8601(0), "1970-01-01T00:00:00.000Z");
+        assert_eq!(iso8601(951_782_400), "2000-02-29T00:00:00.000Z");
+        assert_eq!(iso8601(1_700_000_000), "2023-11-14T22:13:20.000Z");
+    }
+}
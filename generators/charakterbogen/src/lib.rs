@@ -0,0 +1,503 @@
+use std::{collections::BTreeMap, fmt::Display};
+
+use gaeste::{
+    Geschlecht,
+    profession::Profession,
+    random_name,
+    statblock::{Tradition, kampfprofil},
+};
+use generate::{
+    RPGenerator,
+    dsa5::{Eigenschaft, Erfahrungsgrad, Kampftechnik, Steigerungsfaktor, Talent},
+};
+use rand::{Rng, SeedableRng, rngs::StdRng, seq::IndexedRandom};
+use serde::Serialize;
+
+use crate::pakete::{Kultur, Spezies};
+
+mod export;
+pub mod pakete;
+
+/// Startwert jeder Eigenschaft vor dem Kauf
+const EIGENSCHAFT_START: u8 = 8;
+/// Startwert jeder Kampftechnik
+const KTW_START: u8 = 6;
+/// how often we try to spend leftover AP before giving up
+const SPEND_ATTEMPTS: usize = 300;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Held {
+    pub name: String,
+    pub geschlecht: Geschlecht,
+    pub spezies: Spezies,
+    pub kultur: Kultur,
+    pub profession: Profession,
+    pub erfahrungsgrad: Erfahrungsgrad,
+    /// gekaufte Werte ohne Speziesmodifikatoren
+    pub eigenschaften: BTreeMap<Eigenschaft, u8>,
+    /// the free +1 humans and half elves get
+    pub freie_modifikation: Option<Eigenschaft>,
+    pub talente: BTreeMap<Talent, u8>,
+    pub kampftechniken: BTreeMap<Kampftechnik, u8>,
+}
+
+/// Ein Verstoß gegen die Erschaffungsregeln
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verstoss {
+    ApUeberschritten {
+        ausgegeben: u32,
+        budget: u32,
+    },
+    EigenschaftZuHoch {
+        eigenschaft: Eigenschaft,
+        wert: u8,
+        max: u8,
+    },
+    EigenschaftssummeZuHoch {
+        summe: u32,
+        max: u32,
+    },
+    FwZuHoch {
+        talent: Talent,
+        fw: u8,
+        max: u8,
+    },
+    KtwZuHoch {
+        technik: Kampftechnik,
+        ktw: u8,
+        max: u8,
+    },
+    SpeziesPasstNicht {
+        spezies: Spezies,
+        kultur: Kultur,
+    },
+}
+
+impl Display for Verstoss {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ApUeberschritten { ausgegeben, budget } => {
+                write!(f, "{ausgegeben} AP ausgegeben, erlaubt sind {budget}")
+            }
+            Self::EigenschaftZuHoch {
+                eigenschaft,
+                wert,
+                max,
+            } => write!(f, "{eigenschaft} ist {wert}, erlaubt sind {max}"),
+            Self::EigenschaftssummeZuHoch { summe, max } => {
+                write!(f, "Eigenschaftssumme ist {summe}, erlaubt sind {max}")
+            }
+            Self::FwZuHoch { talent, fw, max } => {
+                write!(f, "{talent} hat FW {fw}, erlaubt sind {max}")
+            }
+            Self::KtwZuHoch { technik, ktw, max } => {
+                write!(f, "{technik} hat KtW {ktw}, erlaubt sind {max}")
+            }
+            Self::SpeziesPasstNicht { spezies, kultur } => {
+                write!(f, "{spezies} passt nicht zur Kultur {kultur}")
+            }
+        }
+    }
+}
+
+impl Held {
+    /// final value including species modifiers
+    pub fn eigenschaft(&self, e: Eigenschaft) -> u8 {
+        let gekauft = self.eigenschaften[&e] as i8;
+        let spezies: i8 = self
+            .spezies
+            .modifikatoren()
+            .iter()
+            .filter(|(m, _)| *m == e)
+            .map(|(_, v)| v)
+            .sum();
+        let frei = (self.freie_modifikation == Some(e)) as i8;
+        (gekauft + spezies + frei).max(0) as u8
+    }
+
+    pub fn ap_ausgegeben(&self) -> u32 {
+        let eigenschaften: u32 = self
+            .eigenschaften
+            .values()
+            .map(|&w| Steigerungsfaktor::E.cost(EIGENSCHAFT_START, w))
+            .sum();
+        let talente: u32 = self
+            .talente
+            .iter()
+            .map(|(t, &fw)| t.steigerungsfaktor().cost(0, fw))
+            .sum();
+        let kampftechniken: u32 = self
+            .kampftechniken
+            .iter()
+            .map(|(k, &ktw)| k.steigerungsfaktor().cost(KTW_START, ktw))
+            .sum();
+        self.spezies.ap() + eigenschaften + talente + kampftechniken
+    }
+
+    pub fn ap_budget(&self) -> u32 {
+        self.erfahrungsgrad.ap()
+    }
+
+    /// checks the hero against the rules for the chosen Erfahrungsgrad
+    pub fn validate(&self) -> Result<(), Vec<Verstoss>> {
+        let grad = self.erfahrungsgrad;
+        let mut verstoesse = vec![];
+        if !self.kultur.spezies().contains(&self.spezies) {
+            verstoesse.push(Verstoss::SpeziesPasstNicht {
+                spezies: self.spezies,
+                kultur: self.kultur,
+            });
+        }
+        let ausgegeben = self.ap_ausgegeben();
+        if ausgegeben > self.ap_budget() {
+            verstoesse.push(Verstoss::ApUeberschritten {
+                ausgegeben,
+                budget: self.ap_budget(),
+            });
+        }
+        for (&eigenschaft, &wert) in &self.eigenschaften {
+            if wert > grad.max_eigenschaft() {
+                verstoesse.push(Verstoss::EigenschaftZuHoch {
+                    eigenschaft,
+                    wert,
+                    max: grad.max_eigenschaft(),
+                });
+            }
+        }
+        let summe: u32 = self.eigenschaften.values().map(|&w| w as u32).sum();
+        if summe > grad.max_eigenschaften_summe() {
+            verstoesse.push(Verstoss::EigenschaftssummeZuHoch {
+                summe,
+                max: grad.max_eigenschaften_summe(),
+            });
+        }
+        for (&talent, &fw) in &self.talente {
+            if fw > grad.max_fw() {
+                verstoesse.push(Verstoss::FwZuHoch {
+                    talent,
+                    fw,
+                    max: grad.max_fw(),
+                });
+            }
+        }
+        for (&technik, &ktw) in &self.kampftechniken {
+            if ktw > grad.max_ktw() {
+                verstoesse.push(Verstoss::KtwZuHoch {
+                    technik,
+                    ktw,
+                    max: grad.max_ktw(),
+                });
+            }
+        }
+        if verstoesse.is_empty() {
+            Ok(())
+        } else {
+            Err(verstoesse)
+        }
+    }
+
+    pub fn lep(&self) -> i32 {
+        self.spezies.lep_grundwert() + 2 * self.eigenschaft(Eigenschaft::KO) as i32
+    }
+    pub fn asp(&self) -> Option<i32> {
+        (kampfprofil(self.profession).tradition == Tradition::Zauberer)
+            .then(|| 20 + self.eigenschaft(Eigenschaft::KL) as i32)
+    }
+    pub fn kap(&self) -> Option<i32> {
+        (kampfprofil(self.profession).tradition == Tradition::Geweihter)
+            .then(|| 20 + self.eigenschaft(Eigenschaft::IN) as i32)
+    }
+    pub fn sk(&self) -> i32 {
+        use Eigenschaft::*;
+        let sum = self.eigenschaft(MU) + self.eigenschaft(KL) + self.eigenschaft(IN);
+        self.spezies.sk_zk().0 + sum as i32 / 6
+    }
+    pub fn zk(&self) -> i32 {
+        use Eigenschaft::*;
+        let sum = 2 * self.eigenschaft(KO) + self.eigenschaft(KK);
+        self.spezies.sk_zk().1 + sum as i32 / 6
+    }
+    pub fn ini(&self) -> u8 {
+        (self.eigenschaft(Eigenschaft::MU) + self.eigenschaft(Eigenschaft::GE)) / 2
+    }
+    pub fn aw(&self) -> u8 {
+        self.eigenschaft(Eigenschaft::GE) / 2
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Configuration {
+    pub erfahrungsgrad: Erfahrungsgrad,
+    /// random if None
+    pub kultur: Option<Kultur>,
+    /// random if None, must fit the culture, see [`Configuration::validate`]
+    pub spezies: Option<Spezies>,
+    /// random if None
+    pub profession: Option<Profession>,
+}
+
+impl Default for Configuration {
+    fn default() -> Self {
+        Self {
+            erfahrungsgrad: Erfahrungsgrad::Erfahren,
+            kultur: None,
+            spezies: None,
+            profession: None,
+        }
+    }
+}
+
+impl Configuration {
+    /// a given Spezies has to be one the given Kultur allows
+    pub fn validate(&self) -> Result<(), Verstoss> {
+        match (self.spezies, self.kultur) {
+            (Some(spezies), Some(kultur)) if !kultur.spezies().contains(&spezies) => {
+                Err(Verstoss::SpeziesPasstNicht { spezies, kultur })
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+pub struct Heldengenerator {
+    rng: StdRng,
+    config: Configuration,
+}
+
+impl Heldengenerator {
+    pub fn new(config: Configuration) -> Result<Self, Verstoss> {
+        config.validate()?;
+        Ok(Self {
+            rng: StdRng::from_os_rng(),
+            config,
+        })
+    }
+
+    fn eigenschaften(&mut self, primary: &[Eigenschaft]) -> BTreeMap<Eigenschaft, u8> {
+        let grad = self.config.erfahrungsgrad;
+        let max = grad.max_eigenschaft();
+        let mut werte: BTreeMap<Eigenschaft, u8> = Eigenschaft::ALL
+            .iter()
+            .map(|&e| {
+                let wert = if primary.contains(&e) {
+                    max - self.rng.random_range(0..=1)
+                } else {
+                    self.rng.random_range(10..=13).min(max)
+                };
+                (e, wert)
+            })
+            .collect();
+        // stay below the maximum sum, taking from the secondary attributes first
+        while werte.values().map(|&w| w as u32).sum::<u32>() > grad.max_eigenschaften_summe() {
+            let secondary: Vec<Eigenschaft> = werte
+                .iter()
+                .filter(|&(e, &w)| !primary.contains(e) && w > EIGENSCHAFT_START)
+                .map(|(&e, _)| e)
+                .collect();
+            let e = match secondary.choose(&mut self.rng) {
+                Some(&e) => e,
+                None => *primary.choose(&mut self.rng).unwrap(),
+            };
+            *werte.get_mut(&e).unwrap() -= 1;
+        }
+        werte
+    }
+
+    /// spends leftover AP on random talents of the hero, then on any talent, cheapest last
+    fn spend_leftover(&mut self, held: &mut Held) {
+        let max_fw = held.erfahrungsgrad.max_fw();
+        for _ in 0..SPEND_ATTEMPTS {
+            let rest = held.ap_budget().saturating_sub(held.ap_ausgegeben());
+            let known: Vec<Talent> = held.talente.keys().copied().collect();
+            let talent = if self.rng.random_bool(0.8) {
+                *known.choose(&mut self.rng).unwrap()
+            } else {
+                *Talent::ALL.choose(&mut self.rng).unwrap()
+            };
+            let fw = held.talente.get(&talent).copied().unwrap_or(0);
+            if fw < max_fw && talent.steigerungsfaktor().step_cost(fw + 1) <= rest {
+                held.talente.insert(talent, fw + 1);
+            }
+        }
+        // the dice left some AP over, put them into the cheapest raises
+        while let Some((talent, fw)) = Talent::ALL
+            .iter()
+            .map(|&t| (t, held.talente.get(&t).copied().unwrap_or(0)))
+            .filter(|&(t, fw)| {
+                fw < max_fw
+                    && t.steigerungsfaktor().step_cost(fw + 1)
+                        <= held.ap_budget().saturating_sub(held.ap_ausgegeben())
+            })
+            .min_by_key(|&(t, fw)| t.steigerungsfaktor().step_cost(fw + 1))
+        {
+            held.talente.insert(talent, fw + 1);
+        }
+    }
+
+    /// lowers attributes until the AP budget fits
+    fn trim(&mut self, held: &mut Held) {
+        while held.ap_ausgegeben() > held.ap_budget() {
+            let lowerable: Vec<Eigenschaft> = held
+                .eigenschaften
+                .iter()
+                .filter(|&(_, &w)| w > EIGENSCHAFT_START)
+                .map(|(&e, _)| e)
+                .collect();
+            match lowerable.choose(&mut self.rng) {
+                Some(e) => *held.eigenschaften.get_mut(e).unwrap() -= 1,
+                None => break,
+            }
+        }
+    }
+}
+
+impl Iterator for Heldengenerator {
+    type Item = Held;
+    fn next(&mut self) -> Option<Self::Item> {
+        let grad = self.config.erfahrungsgrad;
+        let kultur = match (self.config.kultur, self.config.spezies) {
+            (Some(k), _) => k,
+            (None, Some(s)) => *Kultur::ALL
+                .into_iter()
+                .filter(|k| k.spezies().contains(&s))
+                .collect::<Vec<_>>()
+                .choose(&mut self.rng)?,
+            (None, None) => *Kultur::ALL.choose(&mut self.rng)?,
+        };
+        let spezies = match self.config.spezies {
+            Some(s) => s,
+            None => *kultur.spezies().choose(&mut self.rng)?,
+        };
+        let profession = match self.config.profession {
+            Some(p) => p,
+            None => *Profession::ALL.choose(&mut self.rng)?,
+        };
+        let geschlecht = if self.rng.random_bool(0.5) {
+            Geschlecht::Maennlich
+        } else {
+            Geschlecht::Weiblich
+        };
+        let profil = kampfprofil(profession);
+
+        let mut talente: BTreeMap<Talent, u8> = BTreeMap::new();
+        for &(talent, fw) in kultur.talente().iter().chain(profession.talents()) {
+            let entry = talente.entry(talent).or_default();
+            *entry = (*entry + fw).min(grad.max_fw());
+        }
+        let mut kampftechniken: BTreeMap<Kampftechnik, u8> =
+            Kampftechnik::ALL.iter().map(|&k| (k, KTW_START)).collect();
+        kampftechniken.insert(
+            profil.waffe.technik,
+            (KTW_START + 2 * profil.kampfkraft + grad as u8).min(grad.max_ktw()),
+        );
+
+        let mut held = Held {
+            name: random_name(&mut self.rng, geschlecht),
+            geschlecht,
+            spezies,
+            kultur,
+            profession,
+            erfahrungsgrad: grad,
+            eigenschaften: self.eigenschaften(profil.primary),
+            freie_modifikation: matches!(spezies, Spezies::Mensch | Spezies::Halbelf)
+                .then(|| profil.primary[0]),
+            talente,
+            kampftechniken,
+        };
+        self.trim(&mut held);
+        self.spend_leftover(&mut held);
+        Some(held)
+    }
+}
+
+impl RPGenerator for Heldengenerator {
+    type Seed = u64;
+    fn seed(&mut self, s: Self::Seed) {
+        self.rng = StdRng::seed_from_u64(s);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use generate::{
+        RPGenerator,
+        dsa5::{Eigenschaft, Erfahrungsgrad},
+    };
+
+    use crate::{
+        Configuration, Heldengenerator, Verstoss,
+        pakete::{Kultur, Spezies},
+    };
+
+    #[test]
+    fn generated_heroes_are_valid() {
+        for grad in Erfahrungsgrad::ALL {
+            let mut generator = Heldengenerator::new(Configuration {
+                erfahrungsgrad: grad,
+                ..Default::default()
+            })
+            .unwrap();
+            generator.seed(11);
+            for held in generator.take(10) {
+                assert_eq!(held.validate(), Ok(()), "{held}");
+                // we should not leave much on the table
+                assert!(held.ap_budget() - held.ap_ausgegeben() < 50, "{held}");
+            }
+        }
+    }
+
+    #[test]
+    fn detects_violations() {
+        let mut generator = Heldengenerator::new(Configuration::default()).unwrap();
+        generator.seed(2);
+        let mut held = generator.next().unwrap();
+        held.eigenschaften.insert(Eigenschaft::MU, 18);
+        let verstoesse = held.validate().unwrap_err();
+        assert!(verstoesse.contains(&Verstoss::EigenschaftZuHoch {
+            eigenschaft: Eigenschaft::MU,
+            wert: 18,
+            max: 14
+        }));
+        assert!(
+            verstoesse
+                .iter()
+                .any(|v| matches!(v, Verstoss::ApUeberschritten { .. }))
+        );
+    }
+
+    #[test]
+    fn spezies_has_to_fit_the_kultur() {
+        let config = |kultur, spezies| Configuration {
+            kultur,
+            spezies,
+            ..Default::default()
+        };
+        assert_eq!(
+            Heldengenerator::new(config(Some(Kultur::Auelfen), Some(Spezies::Zwerg))).err(),
+            Some(Verstoss::SpeziesPasstNicht {
+                spezies: Spezies::Zwerg,
+                kultur: Kultur::Auelfen
+            })
+        );
+        // without a culture one that fits is chosen
+        let mut generator = Heldengenerator::new(config(None, Some(Spezies::Zwerg))).unwrap();
+        generator.seed(5);
+        for held in generator.take(5) {
+            assert_eq!(held.kultur, Kultur::Huegelzwerge);
+        }
+
+        let mut generator = Heldengenerator::new(Configuration::default()).unwrap();
+        generator.seed(5);
+        let mut held = generator.next().unwrap();
+        held.spezies = Spezies::Zwerg;
+        held.kultur = Kultur::Auelfen;
+        assert!(
+            held.validate()
+                .unwrap_err()
+                .contains(&Verstoss::SpeziesPasstNicht {
+                    spezies: Spezies::Zwerg,
+                    kultur: Kultur::Auelfen
+                })
+        );
+    }
+}
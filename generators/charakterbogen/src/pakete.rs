@@ -0,0 +1,201 @@
+//! Spezies- und Kulturpakete.
+//! Professionspakete kommen aus [`gaeste::profession::Profession`].
+//! Kultur- und Professionspakete werden über ihre Inhalte bepreist, nur die Spezies hat eigene AP-Kosten.
+
+use std::fmt::Display;
+
+use generate::dsa5::{Eigenschaft, Talent};
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum Spezies {
+    Mensch,
+    Elf,
+    Halbelf,
+    Zwerg,
+}
+
+impl Spezies {
+    pub const ALL: [Spezies; 4] = [Self::Mensch, Self::Elf, Self::Halbelf, Self::Zwerg];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Mensch => "Mensch",
+            Self::Elf => "Elf",
+            Self::Halbelf => "Halbelf",
+            Self::Zwerg => "Zwerg",
+        }
+    }
+    pub fn ap(self) -> u32 {
+        match self {
+            Self::Mensch | Self::Halbelf => 0,
+            Self::Elf => 18,
+            Self::Zwerg => 61,
+        }
+    }
+    pub fn lep_grundwert(self) -> i32 {
+        match self {
+            Self::Mensch | Self::Halbelf => 5,
+            Self::Elf => 2,
+            Self::Zwerg => 8,
+        }
+    }
+    /// Grundwerte für Seelenkraft und Zähigkeit
+    pub fn sk_zk(self) -> (i32, i32) {
+        match self {
+            Self::Mensch => (-5, -5),
+            Self::Elf | Self::Halbelf => (-4, -6),
+            Self::Zwerg => (-4, -4),
+        }
+    }
+    pub fn gs(self) -> u32 {
+        match self {
+            Self::Zwerg => 6,
+            _ => 8,
+        }
+    }
+    /// Modifikatoren auf Eigenschaften, die nicht mit AP bezahlt werden
+    pub fn modifikatoren(self) -> &'static [(Eigenschaft, i8)] {
+        use Eigenschaft::*;
+        match self {
+            Self::Mensch | Self::Halbelf => &[],
+            Self::Elf => &[(IN, 1), (GE, 1), (KK, -2)],
+            Self::Zwerg => &[(KO, 1), (KK, 1), (CH, -2)],
+        }
+    }
+    /// Optolith id
+    pub fn optolith_id(self) -> &'static str {
+        match self {
+            Self::Mensch => "R_1",
+            Self::Elf => "R_2",
+            Self::Halbelf => "R_3",
+            Self::Zwerg => "R_4",
+        }
+    }
+}
+
+impl Display for Spezies {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum Kultur {
+    Mittelreicher,
+    Horasier,
+    Thorwaler,
+    Bornlaender,
+    Novadi,
+    Andergaster,
+    Auelfen,
+    Huegelzwerge,
+}
+
+impl Kultur {
+    pub const ALL: [Kultur; 8] = [
+        Self::Mittelreicher,
+        Self::Horasier,
+        Self::Thorwaler,
+        Self::Bornlaender,
+        Self::Novadi,
+        Self::Andergaster,
+        Self::Auelfen,
+        Self::Huegelzwerge,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Mittelreicher => "Mittelreicher",
+            Self::Horasier => "Horasier",
+            Self::Thorwaler => "Thorwaler",
+            Self::Bornlaender => "Bornländer",
+            Self::Novadi => "Novadi",
+            Self::Andergaster => "Andergaster",
+            Self::Auelfen => "Auelfen",
+            Self::Huegelzwerge => "Hügelzwerge",
+        }
+    }
+
+    /// welche Spezies in dieser Kultur aufwachsen kann
+    pub fn spezies(self) -> &'static [Spezies] {
+        match self {
+            Self::Auelfen => &[Spezies::Elf, Spezies::Halbelf],
+            Self::Huegelzwerge => &[Spezies::Zwerg],
+            _ => &[Spezies::Mensch, Spezies::Halbelf],
+        }
+    }
+
+    pub fn talente(self) -> &'static [(Talent, u8)] {
+        use Talent::*;
+        match self {
+            Self::Mittelreicher => &[
+                (Etikette, 2),
+                (GoetterKulte, 3),
+                (Reiten, 2),
+                (Zechen, 2),
+                (Rechnen, 1),
+            ],
+            Self::Horasier => &[
+                (Etikette, 4),
+                (Tanzen, 2),
+                (Betoeren, 2),
+                (Geschichtswissen, 2),
+                (Rechtskunde, 1),
+            ],
+            Self::Thorwaler => &[
+                (BooteSchiffe, 4),
+                (Schwimmen, 3),
+                (Zechen, 4),
+                (Einschuechtern, 2),
+                (Klettern, 2),
+            ],
+            Self::Bornlaender => &[(Reiten, 3), (Wildnisleben, 2), (Zechen, 3), (Handel, 2)],
+            Self::Novadi => &[
+                (Reiten, 4),
+                (Orientierung, 3),
+                (Wildnisleben, 3),
+                (GoetterKulte, 2),
+            ],
+            Self::Andergaster => &[
+                (Holzbearbeitung, 3),
+                (Wildnisleben, 2),
+                (Pflanzenkunde, 2),
+                (Kraftakt, 2),
+            ],
+            Self::Auelfen => &[
+                (Singen, 4),
+                (Musizieren, 3),
+                (Tierkunde, 3),
+                (Sinnesschaerfe, 3),
+                (Pflanzenkunde, 2),
+            ],
+            Self::Huegelzwerge => &[
+                (Zechen, 4),
+                (Lebensmittelbearbeitung, 3),
+                (Handel, 2),
+                (Metallbearbeitung, 2),
+            ],
+        }
+    }
+
+    /// Optolith id, numbered like the cultures in the DSA5 Regelwerk
+    pub fn optolith_id(self) -> &'static str {
+        match self {
+            Self::Andergaster => "C_1",
+            Self::Bornlaender => "C_3",
+            Self::Horasier => "C_5",
+            Self::Mittelreicher => "C_8",
+            Self::Novadi => "C_14",
+            Self::Thorwaler => "C_17",
+            Self::Auelfen => "C_19",
+            Self::Huegelzwerge => "C_25",
+        }
+    }
+}
+
+impl Display for Kultur {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
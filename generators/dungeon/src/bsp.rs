@@ -0,0 +1,142 @@
+//! Binary space partitioning: the map is split recursively until the parts are small,
+//! every leaf gets a room and sibling subtrees are connected by a corridor.
+
+use rand::Rng;
+
+/// a wall tile on each side and at least one tile of floor in between
+pub const MIN_LEAF: u32 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rect {
+    pub x: u32,
+    pub y: u32,
+    pub w: u32,
+    pub h: u32,
+}
+
+impl Rect {
+    pub fn center(&self) -> (u32, u32) {
+        (self.x + self.w / 2, self.y + self.h / 2)
+    }
+    pub fn intersects(&self, other: &Rect) -> bool {
+        self.x < other.x + other.w
+            && other.x < self.x + self.w
+            && self.y < other.y + other.h
+            && other.y < self.y + self.h
+    }
+}
+
+/// a corridor is an L-shape: horizontal from `from` then vertical to `to`
+#[derive(Debug, Clone, Copy)]
+pub struct Corridor {
+    pub from: (u32, u32),
+    pub to: (u32, u32),
+}
+
+pub struct Layout {
+    pub rooms: Vec<Rect>,
+    pub corridors: Vec<Corridor>,
+}
+
+/// splits `area` and returns rooms and corridors.
+/// `min_leaf` is the smallest partition size, rooms keep a margin of one tile to the partition border.
+/// It is raised to [`MIN_LEAF`], an area smaller than that stays empty.
+pub fn layout(rng: &mut impl Rng, area: Rect, min_leaf: u32) -> Layout {
+    let mut layout = Layout {
+        rooms: vec![],
+        corridors: vec![],
+    };
+    if area.w >= MIN_LEAF && area.h >= MIN_LEAF {
+        split(rng, area, min_leaf.max(MIN_LEAF), &mut layout);
+    }
+    layout
+}
+
+/// returns a point inside one of the rooms of this subtree to connect to
+fn split(rng: &mut impl Rng, area: Rect, min_leaf: u32, layout: &mut Layout) -> (u32, u32) {
+    let can_split_h = area.h >= 2 * min_leaf;
+    let can_split_v = area.w >= 2 * min_leaf;
+    let horizontal = match (can_split_h, can_split_v) {
+        (false, false) => return carve_room(rng, area, layout),
+        (true, false) => true,
+        (false, true) => false,
+        // prefer cutting the long side
+        (true, true) => {
+            if area.w as f32 / area.h as f32 > 1.25 {
+                false
+            } else if area.h as f32 / area.w as f32 > 1.25 {
+                true
+            } else {
+                rng.random_bool(0.5)
+            }
+        }
+    };
+    let (a, b) = if horizontal {
+        let cut = rng.random_range(min_leaf..=area.h - min_leaf);
+        (
+            Rect { h: cut, ..area },
+            Rect {
+                y: area.y + cut,
+                h: area.h - cut,
+                ..area
+            },
+        )
+    } else {
+        let cut = rng.random_range(min_leaf..=area.w - min_leaf);
+        (
+            Rect { w: cut, ..area },
+            Rect {
+                x: area.x + cut,
+                w: area.w - cut,
+                ..area
+            },
+        )
+    };
+    let from = split(rng, a, min_leaf, layout);
+    let to = split(rng, b, min_leaf, layout);
+    layout.corridors.push(Corridor { from, to });
+    if rng.random_bool(0.5) { from } else { to }
+}
+
+fn carve_room(rng: &mut impl Rng, area: Rect, layout: &mut Layout) -> (u32, u32) {
+    // leave one tile of wall on each side
+    let max_w = area.w.saturating_sub(2).max(1);
+    let max_h = area.h.saturating_sub(2).max(1);
+    let w = rng.random_range(max_w.div_ceil(2)..=max_w);
+    let h = rng.random_range(max_h.div_ceil(2)..=max_h);
+    let x = area.x + 1 + rng.random_range(0..=max_w - w);
+    let y = area.y + 1 + rng.random_range(0..=max_h - h);
+    let room = Rect { x, y, w, h };
+    layout.rooms.push(room);
+    room.center()
+}
+
+#[cfg(test)]
+mod test {
+    use rand::{SeedableRng, rngs::StdRng};
+
+    use super::{Rect, layout};
+
+    #[test]
+    fn degenerate_partitions() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let area = Rect {
+            x: 0,
+            y: 0,
+            w: 20,
+            h: 12,
+        };
+        // a min_leaf of 0 used to split the same rect forever
+        for min_leaf in 0..4 {
+            let layout = layout(&mut rng, area, min_leaf);
+            assert!(!layout.rooms.is_empty());
+            for room in &layout.rooms {
+                assert!(room.w > 0 && room.h > 0);
+                assert!(room.x > 0 && room.x + room.w < area.w);
+                assert!(room.y > 0 && room.y + room.h < area.h);
+            }
+        }
+        let tiny = Rect { w: 2, ..area };
+        assert!(layout(&mut rng, tiny, 1).rooms.is_empty());
+    }
+}
@@ -0,0 +1,105 @@
+use std::{fmt::Display, path::Path};
+
+use image::{Rgba, RgbaImage};
+use serde::{Deserialize, Serialize};
+
+use crate::{Dungeon, Tile};
+
+const WALL: Rgba<u8> = Rgba([40, 36, 32, 255]);
+const FLOOR: Rgba<u8> = Rgba([196, 180, 150, 255]);
+const CORRIDOR: Rgba<u8> = Rgba([160, 146, 120, 255]);
+const GRID: Rgba<u8> = Rgba([120, 108, 90, 255]);
+
+/// A named area of the map, given as a polygon in map pixels (origin top left, y pointing down).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Region {
+    pub name: String,
+    pub polygon: Vec<[f32; 2]>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegionFile {
+    #[serde(rename = "region")]
+    pub regions: Vec<Region>,
+}
+
+#[derive(Debug)]
+pub enum ExportError {
+    Image(image::ImageError),
+    Io(std::io::Error),
+    Serialize(toml::ser::Error),
+}
+
+impl Display for ExportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Image(e) => write!(f, "Karte konnte nicht gespeichert werden: {e}"),
+            Self::Io(e) => write!(f, "Regionen konnten nicht gespeichert werden: {e}"),
+            Self::Serialize(e) => write!(f, "Regionen konnten nicht serialisiert werden: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for ExportError {}
+
+impl Dungeon {
+    pub fn render(&self, tile_px: u32) -> RgbaImage {
+        let mut img = RgbaImage::from_pixel(self.width * tile_px, self.height * tile_px, WALL);
+        for ty in 0..self.height {
+            for tx in 0..self.width {
+                let color = match self.tile(tx, ty) {
+                    Tile::Wall => continue,
+                    Tile::Floor => FLOOR,
+                    Tile::Corridor => CORRIDOR,
+                };
+                for py in 0..tile_px {
+                    for px in 0..tile_px {
+                        // thin grid lines on the tile borders
+                        let c = if px == 0 || py == 0 { GRID } else { color };
+                        img.put_pixel(tx * tile_px + px, ty * tile_px + py, c);
+                    }
+                }
+            }
+        }
+        img
+    }
+
+    /// one region per room, in the pixel coordinates of [`Dungeon::render`]
+    pub fn regions(&self, tile_px: u32) -> RegionFile {
+        let regions = self
+            .rooms
+            .iter()
+            .map(|raum| {
+                let r = raum.rect;
+                let (x0, y0) = ((r.x * tile_px) as f32, (r.y * tile_px) as f32);
+                let (x1, y1) = (
+                    ((r.x + r.w) * tile_px) as f32,
+                    ((r.y + r.h) * tile_px) as f32,
+                );
+                Region {
+                    name: raum.name.clone(),
+                    polygon: vec![[x0, y0], [x1, y0], [x1, y1], [x0, y1]],
+                    notes: Some(raum.to_string()),
+                }
+            })
+            .collect();
+        RegionFile { regions }
+    }
+
+    /// writes `<name>.png` and `<name>.regions.toml` into `dir`
+    pub fn export(
+        &self,
+        dir: impl AsRef<Path>,
+        name: &str,
+        tile_px: u32,
+    ) -> Result<(), ExportError> {
+        let dir = dir.as_ref();
+        self.render(tile_px)
+            .save(dir.join(format!("{name}.png")))
+            .map_err(ExportError::Image)?;
+        let regions = toml::to_string(&self.regions(tile_px)).map_err(ExportError::Serialize)?;
+        std::fs::write(dir.join(format!("{name}.regions.toml")), regions).map_err(ExportError::Io)
+    }
+}
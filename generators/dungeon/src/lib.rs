@@ -0,0 +1,324 @@
+use std::fmt::Display;
+
+use generate::RPGenerator;
+use rand::{Rng, SeedableRng, rngs::StdRng, seq::IndexedRandom};
+use schatzi::hoard::{Hoard, HoardConfig, HoardGenerator, Theme};
+
+use crate::bsp::{MIN_LEAF, Rect};
+
+pub mod bsp;
+pub mod export;
+
+/// name, how many of them show up
+const BEGEGNUNGEN: &[(&str, (u32, u32))] = &[
+    ("Riesenratten", (2, 6)),
+    ("Goblins", (2, 5)),
+    ("Skelette", (1, 4)),
+    ("Höhlenspinne", (1, 1)),
+    ("Räuber", (2, 4)),
+    ("Grolm", (1, 1)),
+    ("Oger", (1, 1)),
+    ("Zombies", (2, 3)),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tile {
+    Wall,
+    Floor,
+    Corridor,
+}
+
+#[derive(Debug, Clone)]
+pub struct Raum {
+    pub name: String,
+    pub rect: Rect,
+    pub begegnung: Option<String>,
+    pub beute: Option<Hoard>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Dungeon {
+    pub width: u32,
+    pub height: u32,
+    tiles: Vec<Tile>,
+    pub rooms: Vec<Raum>,
+}
+
+impl Dungeon {
+    pub fn tile(&self, x: u32, y: u32) -> Tile {
+        self.tiles[(y * self.width + x) as usize]
+    }
+    fn set(&mut self, x: u32, y: u32, tile: Tile) {
+        let idx = (y * self.width + x) as usize;
+        // corridors never overwrite room floor
+        if !(tile == Tile::Corridor && self.tiles[idx] == Tile::Floor) {
+            self.tiles[idx] = tile;
+        }
+    }
+}
+
+/// Map size in tiles
+#[derive(Debug, Clone, Copy)]
+pub struct Configuration {
+    pub width: u32,
+    pub height: u32,
+    /// smallest BSP partition, rooms are at most two tiles smaller
+    pub min_leaf: u32,
+    /// challenge level for the treasure
+    pub level: u8,
+}
+
+impl Default for Configuration {
+    fn default() -> Self {
+        Self {
+            width: 48,
+            height: 32,
+            min_leaf: 8,
+            level: 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigError {
+    /// below [`MIN_LEAF`] there is no room for a room
+    MinLeaf(u32),
+    /// the map does not even fit a single partition
+    TooSmall { width: u32, height: u32 },
+}
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MinLeaf(min_leaf) => {
+                write!(
+                    f,
+                    "min_leaf muss mindestens {MIN_LEAF} sein, nicht {min_leaf}"
+                )
+            }
+            Self::TooSmall { width, height } => {
+                write!(f, "eine Karte von {width}x{height} Feldern ist zu klein")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl Configuration {
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.min_leaf < MIN_LEAF {
+            return Err(ConfigError::MinLeaf(self.min_leaf));
+        }
+        if self.width < self.min_leaf || self.height < self.min_leaf {
+            return Err(ConfigError::TooSmall {
+                width: self.width,
+                height: self.height,
+            });
+        }
+        Ok(())
+    }
+}
+
+pub struct Dungeongenerator {
+    rng: StdRng,
+    hoards: HoardGenerator,
+    config: Configuration,
+}
+
+impl Dungeongenerator {
+    pub fn new(config: Configuration) -> Result<Self, ConfigError> {
+        config.validate()?;
+        Ok(Self {
+            rng: StdRng::from_os_rng(),
+            hoards: HoardGenerator::new(HoardConfig::default()),
+            config,
+        })
+    }
+
+    /// bandits keep a Räuberlager, anything else guards a forgotten shrine or someone's stash
+    fn beute(&mut self, begegnung: Option<&str>) -> Option<Hoard> {
+        let theme = if begegnung.is_some_and(|b| b.contains("Räuber")) {
+            Theme::Raeuberlager
+        } else {
+            *[Theme::Raeuberlager, Theme::Tempelschatz].choose(&mut self.rng)?
+        };
+        self.hoards.set_config(HoardConfig {
+            level: self.config.level,
+            theme: Some(theme),
+            ..Default::default()
+        });
+        self.hoards.next()
+    }
+
+    fn begegnung(&mut self) -> Option<String> {
+        let (name, (min, max)) = BEGEGNUNGEN.choose(&mut self.rng)?;
+        let count = self.rng.random_range(*min..=*max);
+        Some(if count == 1 {
+            name.to_string()
+        } else {
+            format!("{count} {name}")
+        })
+    }
+}
+
+impl Iterator for Dungeongenerator {
+    type Item = Dungeon;
+    fn next(&mut self) -> Option<Self::Item> {
+        let Configuration {
+            width,
+            height,
+            min_leaf,
+            ..
+        } = self.config;
+        let layout = bsp::layout(
+            &mut self.rng,
+            Rect {
+                x: 0,
+                y: 0,
+                w: width,
+                h: height,
+            },
+            min_leaf,
+        );
+        let mut dungeon = Dungeon {
+            width,
+            height,
+            tiles: vec![Tile::Wall; (width * height) as usize],
+            rooms: vec![],
+        };
+        for r in &layout.rooms {
+            for y in r.y..r.y + r.h {
+                for x in r.x..r.x + r.w {
+                    dungeon.set(x, y, Tile::Floor);
+                }
+            }
+        }
+        for c in &layout.corridors {
+            let ((x0, y0), (x1, y1)) = (c.from, c.to);
+            for x in x0.min(x1)..=x0.max(x1) {
+                dungeon.set(x, y0, Tile::Corridor);
+            }
+            for y in y0.min(y1)..=y0.max(y1) {
+                dungeon.set(x1, y, Tile::Corridor);
+            }
+        }
+        for (i, rect) in layout.rooms.into_iter().enumerate() {
+            // the first room is the entrance and stays empty
+            let (begegnung, beute) = if i == 0 {
+                (None, None)
+            } else {
+                let begegnung = if self.rng.random_bool(0.5) {
+                    self.begegnung()
+                } else {
+                    None
+                };
+                let beute = if self.rng.random_bool(0.4) {
+                    self.beute(begegnung.as_deref())
+                } else {
+                    None
+                };
+                (begegnung, beute)
+            };
+            dungeon.rooms.push(Raum {
+                name: format!("Raum {}", i + 1),
+                rect,
+                begegnung,
+                beute,
+            });
+        }
+        Some(dungeon)
+    }
+}
+
+impl RPGenerator for Dungeongenerator {
+    type Seed = u64;
+    fn seed(&mut self, s: Self::Seed) {
+        self.rng = StdRng::seed_from_u64(s);
+        self.hoards.seed(s.wrapping_add(1));
+    }
+}
+
+impl Display for Raum {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({}x{} Felder)", self.name, self.rect.w, self.rect.h)?;
+        if let Some(b) = &self.begegnung {
+            write!(f, ", Begegnung: {b}")?;
+        }
+        if let Some(hoard) = &self.beute {
+            write!(f, ", Beute: {} im Wert von {}", hoard.theme, hoard.value())?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for Dungeon {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let c = match self.tile(x, y) {
+                    Tile::Wall => '#',
+                    Tile::Floor => '.',
+                    Tile::Corridor => ',',
+                };
+                write!(f, "{c}")?;
+            }
+            writeln!(f)?;
+        }
+        for raum in &self.rooms {
+            writeln!(f, "{raum}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use generate::RPGenerator;
+
+    use crate::{ConfigError, Configuration, Dungeongenerator, export::RegionFile};
+
+    #[test]
+    fn rooms_are_separate_and_inside() {
+        let config = Configuration::default();
+        let mut generator = Dungeongenerator::new(config).unwrap();
+        generator.seed(3);
+        for dungeon in generator.take(20) {
+            assert!(dungeon.rooms.len() > 1);
+            for (i, a) in dungeon.rooms.iter().enumerate() {
+                assert!(a.rect.x + a.rect.w <= config.width);
+                assert!(a.rect.y + a.rect.h <= config.height);
+                for b in &dungeon.rooms[i + 1..] {
+                    assert!(!a.rect.intersects(&b.rect));
+                }
+            }
+            let regions = toml::to_string(&dungeon.regions(16)).unwrap();
+            let parsed: RegionFile = toml::from_str(&regions).unwrap();
+            assert_eq!(parsed.regions.len(), dungeon.rooms.len());
+            assert_eq!(dungeon.render(16).width(), config.width * 16);
+        }
+    }
+
+    #[test]
+    fn degenerate_configurations() {
+        let config = |width, height, min_leaf| Configuration {
+            width,
+            height,
+            min_leaf,
+            ..Default::default()
+        };
+        for min_leaf in 0..3 {
+            assert_eq!(
+                Dungeongenerator::new(config(48, 32, min_leaf)).err(),
+                Some(ConfigError::MinLeaf(min_leaf))
+            );
+        }
+        assert!(matches!(
+            Dungeongenerator::new(config(6, 32, 8)),
+            Err(ConfigError::TooSmall { .. })
+        ));
+        // the smallest map that is allowed still gets a room
+        let mut generator = Dungeongenerator::new(config(3, 3, 3)).unwrap();
+        assert_eq!(generator.next().unwrap().rooms.len(), 1);
+    }
+}
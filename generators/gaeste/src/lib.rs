@@ -1,29 +1,206 @@
-use generate::RPGenerator;
+use std::fmt::Display;
 
-struct Gaestegenerator;
+use generate::{RPGenerator, dsa5::Erfahrungsgrad};
+use rand::{Rng, SeedableRng, rngs::StdRng, seq::IndexedRandom};
+use serde::Serialize;
 
-struct Gast {
-    profession: Profession,
-    specialty: Specialty,
-    teachable_skills: Vec<Skills>
-    name: Name,
-    special_items: Vec<Items>,
+use crate::{
+    profession::Profession,
+    statblock::Statblock,
+    teaching::{MIN_TEACHING_FW, TeachingOffer},
+};
+
+pub mod profession;
+pub mod statblock;
+pub mod teaching;
+
+const MALE_NAMES: &[&str] = &[
+    "Alrik", "Answin", "Boronian", "Cordovan", "Elgor", "Gerion", "Hakon", "Jost", "Leomar",
+    "Norbert", "Praiodan", "Rondrian", "Shafir", "Ulfried", "Vitold", "Zordan",
+];
+const FEMALE_NAMES: &[&str] = &[
+    "Alrike", "Belima", "Danja", "Fenia", "Gunelde", "Ifirnia", "Kusmina", "Linnea", "Mirhiban",
+    "Odila", "Rahjane", "Travine", "Wulfhild", "Yasmina",
+];
+const LAST_NAMES: &[&str] = &[
+    "aus Gareth",
+    "von Ferdok",
+    "Ehrwald",
+    "Gerbensen",
+    "Grassberger",
+    "Hammerschlag",
+    "Krähwinkel",
+    "ibn Jassafer",
+    "Rosenhain",
+    "Steinwalder",
+    "Thorwalsdottir",
+    "Weißdorn",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum Geschlecht {
+    Maennlich,
+    Weiblich,
 }
 
-struct Configuration{
-    
+pub fn random_name(rng: &mut impl Rng, geschlecht: Geschlecht) -> String {
+    let first = match geschlecht {
+        Geschlecht::Maennlich => MALE_NAMES,
+        Geschlecht::Weiblich => FEMALE_NAMES,
+    };
+    format!(
+        "{} {}",
+        first.choose(rng).unwrap(),
+        LAST_NAMES.choose(rng).unwrap()
+    )
+}
+
+/// how many talents a guest can teach at most
+const MAX_TEACHABLE: usize = 3;
+
+pub struct Gaestegenerator {
+    rng: StdRng,
+    config: Configuration,
+}
+
+pub struct Gast {
+    pub name: String,
+    pub profession: Profession,
+    pub specialty: &'static str,
+    pub teachable_skills: Vec<TeachingOffer>,
+    pub special_items: Vec<&'static str>,
+    pub statblock: Option<Statblock>,
+}
+
+#[derive(Debug, Default)]
+pub struct Configuration {
+    /// generate a statblock at the given experience level
+    pub statblock: Option<Erfahrungsgrad>,
+}
+
+impl Gaestegenerator {
+    pub fn new(config: Configuration) -> Self {
+        Self {
+            rng: StdRng::from_os_rng(),
+            config,
+        }
+    }
+
+    fn teachable_skills(&mut self, profession: Profession) -> Vec<TeachingOffer> {
+        let mut offers: Vec<TeachingOffer> = profession
+            .talents()
+            .iter()
+            .map(|&(talent, fw)| {
+                (
+                    talent,
+                    fw.saturating_add_signed(self.rng.random_range(-3..=4)),
+                )
+            })
+            .filter(|&(_, fw)| fw >= MIN_TEACHING_FW)
+            .map(|(talent, fw)| TeachingOffer::new(talent, fw))
+            .collect();
+        let count = self.rng.random_range(0..=MAX_TEACHABLE.min(offers.len()));
+        offers.sort_by_key(|o| std::cmp::Reverse(o.max_fw));
+        offers.truncate(count);
+        offers
+    }
 }
 
 impl Iterator for Gaestegenerator {
     type Item = Gast;
-    fn next(&mut self) -> Option<Self::Item>{
-        None
+    fn next(&mut self) -> Option<Self::Item> {
+        let geschlecht = if self.rng.random_bool(0.5) {
+            Geschlecht::Maennlich
+        } else {
+            Geschlecht::Weiblich
+        };
+        let name = random_name(&mut self.rng, geschlecht);
+        let profession = *Profession::ALL.choose(&mut self.rng)?;
+        let specialty = *profession.specialties().choose(&mut self.rng)?;
+        let teachable_skills = self.teachable_skills(profession);
+        let item_count = self.rng.random_range(0..=2);
+        let special_items = profession
+            .special_items()
+            .choose_multiple(&mut self.rng, item_count)
+            .copied()
+            .collect();
+        let statblock = self
+            .config
+            .statblock
+            .map(|grad| Statblock::generate(&mut self.rng, profession, grad));
+        Some(Gast {
+            name,
+            profession,
+            specialty,
+            teachable_skills,
+            special_items,
+            statblock,
+        })
     }
 }
+
 impl RPGenerator for Gaestegenerator {
     type Seed = u64;
-    fn seed(&mut self, _s: u64) {
-        todo!()
+    fn seed(&mut self, s: u64) {
+        self.rng = StdRng::seed_from_u64(s);
+    }
+}
+
+impl Display for Gast {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}, {} ({})", self.name, self.profession, self.specialty)?;
+        if !self.teachable_skills.is_empty() {
+            writeln!(f, "Kann lehren:")?;
+            for offer in &self.teachable_skills {
+                writeln!(f, "  - {offer}")?;
+            }
+        }
+        if !self.special_items.is_empty() {
+            writeln!(f, "Dabei: {}", self.special_items.join(", "))?;
+        }
+        if let Some(statblock) = &self.statblock {
+            writeln!(f, "{statblock}")?;
+        }
+        Ok(())
     }
-    fn new()
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod test {
+    use generate::{
+        RPGenerator,
+        dsa5::{Eigenschaft, Erfahrungsgrad},
+    };
+
+    use crate::{Configuration, Gaestegenerator};
+
+    #[test]
+    fn statblock_respects_erfahrungsgrad() {
+        let mut last_at = 0;
+        for grad in Erfahrungsgrad::ALL {
+            let mut generator = Gaestegenerator::new(Configuration {
+                statblock: Some(grad),
+            });
+            generator.seed(7);
+            let mut at = 0;
+            for gast in generator.take(20) {
+                let statblock = gast.statblock.unwrap();
+                at += statblock.at;
+                // the armour slows down
+                let be = statblock.ruestung.be as u32;
+                let mu_ge = statblock.eigenschaften.get(Eigenschaft::MU) as u32
+                    + statblock.eigenschaften.get(Eigenschaft::GE) as u32;
+                assert_eq!(statblock.ini + be, mu_ge / 2);
+                assert_eq!(statblock.gs + be, 8);
+                for e in Eigenschaft::ALL {
+                    assert!(statblock.eigenschaften.get(e) <= grad.max_eigenschaft());
+                }
+                let json: serde_json::Value = serde_json::from_str(&statblock.to_json()).unwrap();
+                assert_eq!(json["lep"], statblock.lep);
+            }
+            // more experience, better fighters
+            assert!(at > last_at, "{grad}");
+            last_at = at;
+        }
+    }
+}
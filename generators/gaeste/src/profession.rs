@@ -0,0 +1,184 @@
+use std::fmt::Display;
+
+use generate::dsa5::Talent;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum Profession {
+    Schmied,
+    Haendler,
+    Soeldner,
+    Gelehrter,
+    Barde,
+    Jaeger,
+    Heiler,
+    Geweihter,
+    Magier,
+    Dieb,
+    Seefahrer,
+    Bauer,
+}
+
+impl Profession {
+    pub const ALL: &'static [Profession] = &[
+        Self::Schmied,
+        Self::Haendler,
+        Self::Soeldner,
+        Self::Gelehrter,
+        Self::Barde,
+        Self::Jaeger,
+        Self::Heiler,
+        Self::Geweihter,
+        Self::Magier,
+        Self::Dieb,
+        Self::Seefahrer,
+        Self::Bauer,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Schmied => "Schmied",
+            Self::Haendler => "Händler",
+            Self::Soeldner => "Söldner",
+            Self::Gelehrter => "Gelehrter",
+            Self::Barde => "Barde",
+            Self::Jaeger => "Jäger",
+            Self::Heiler => "Heiler",
+            Self::Geweihter => "Geweihter",
+            Self::Magier => "Magier",
+            Self::Dieb => "Dieb",
+            Self::Seefahrer => "Seefahrer",
+            Self::Bauer => "Bauer",
+        }
+    }
+
+    pub fn specialties(self) -> &'static [&'static str] {
+        match self {
+            Self::Schmied => &["Waffenschmied", "Hufschmied", "Grobschmied", "Goldschmied"],
+            Self::Haendler => &["Tuchhändler", "Gewürzhändler", "Pferdehändler", "Hausierer"],
+            Self::Soeldner => &["Armbrustschütze", "Pikenier", "Schwertgesell", "Reiter"],
+            Self::Gelehrter => &[
+                "Historiker",
+                "Sternkundiger",
+                "Rechtsgelehrter",
+                "Kartograph",
+            ],
+            Self::Barde => &["Spielmann", "Skalde", "Sänger", "Geschichtenerzähler"],
+            Self::Jaeger => &["Fallensteller", "Pirschjäger", "Fährtenleser"],
+            Self::Heiler => &["Wundarzt", "Kräuterkundige", "Bader", "Hebamme"],
+            Self::Geweihter => &["Perainegeweihter", "Travia-Geweihter", "Phexgeweihter"],
+            Self::Magier => &["Antimagier", "Hellsichtmagier", "Elementarist"],
+            Self::Dieb => &["Taschendieb", "Einbrecher", "Hehler"],
+            Self::Seefahrer => &["Steuermann", "Bootsmann", "Navigator"],
+            Self::Bauer => &["Viehzüchter", "Winzer", "Imker"],
+        }
+    }
+
+    /// Talente, die typisch für die Profession sind, mit einem üblichen FW
+    pub fn talents(self) -> &'static [(Talent, u8)] {
+        use Talent::*;
+        match self {
+            Self::Schmied => &[(Metallbearbeitung, 12), (Kraftakt, 8), (Handel, 5)],
+            Self::Haendler => &[
+                (Handel, 12),
+                (Rechnen, 9),
+                (Menschenkenntnis, 8),
+                (Ueberreden, 8),
+                (Geographie, 6),
+            ],
+            Self::Soeldner => &[
+                (Kriegskunst, 6),
+                (Zechen, 7),
+                (Einschuechtern, 7),
+                (Selbstbeherrschung, 6),
+                (Reiten, 6),
+            ],
+            Self::Gelehrter => &[
+                (Geschichtswissen, 11),
+                (Rechnen, 10),
+                (Rechtskunde, 8),
+                (Sternkunde, 8),
+                (Geographie, 9),
+            ],
+            Self::Barde => &[
+                (Musizieren, 12),
+                (Singen, 11),
+                (SagenLegenden, 9),
+                (Betoeren, 7),
+                (Tanzen, 7),
+            ],
+            Self::Jaeger => &[
+                (Faehrtensuchen, 11),
+                (Wildnisleben, 10),
+                (Tierkunde, 8),
+                (Verbergen, 8),
+                (Fesseln, 6),
+            ],
+            Self::Heiler => &[
+                (HeilkundeWunden, 11),
+                (HeilkundeKrankheiten, 9),
+                (Pflanzenkunde, 9),
+                (HeilkundeGift, 7),
+                (HeilkundeSeele, 6),
+            ],
+            Self::Geweihter => &[
+                (GoetterKulte, 12),
+                (BekehrenUeberzeugen, 9),
+                (Etikette, 7),
+                (Willenskraft, 8),
+                (HeilkundeSeele, 6),
+            ],
+            Self::Magier => &[
+                (Magiekunde, 12),
+                (Sphaerenkunde, 9),
+                (Rechnen, 8),
+                (Alchimie, 7),
+                (Willenskraft, 7),
+            ],
+            Self::Dieb => &[
+                (Taschendiebstahl, 11),
+                (Schloesserknacken, 9),
+                (Gassenwissen, 10),
+                (Verbergen, 9),
+                (Klettern, 7),
+            ],
+            Self::Seefahrer => &[
+                (BooteSchiffe, 12),
+                (Klettern, 8),
+                (Schwimmen, 8),
+                (Orientierung, 9),
+                (Zechen, 6),
+            ],
+            Self::Bauer => &[
+                (Tierkunde, 8),
+                (Pflanzenkunde, 7),
+                (Fahrzeuge, 6),
+                (Lebensmittelbearbeitung, 7),
+                (Kraftakt, 6),
+            ],
+        }
+    }
+
+    pub fn special_items(self) -> &'static [&'static str] {
+        match self {
+            Self::Schmied => &["Schmiedehammer", "Meisterbrief der Gilde"],
+            Self::Haendler => &["Waage mit Gewichten", "Schuldschein", "Musterkoffer"],
+            Self::Soeldner => &["Soldbuch", "Trophäe eines Orks"],
+            Self::Gelehrter => &["Foliant", "Astrolabium", "Empfehlungsschreiben"],
+            Self::Barde => &["Laute", "Liederbuch"],
+            Self::Jaeger => &["Wolfsfell", "Kurzbogen", "Fallen"],
+            Self::Heiler => &["Kräutertasche", "Chirurgisches Besteck"],
+            Self::Geweihter => &["Heiliges Symbol", "Gebetsbuch"],
+            Self::Magier => &["Magierstab", "Zauberbuch", "Gildensiegel"],
+            Self::Dieb => &["Dietrichset", "Gezinkte Würfel"],
+            Self::Seefahrer => &["Kompass", "Seekarte", "Buddelschiff"],
+            Self::Bauer => &["Sense", "Korb mit Eiern"],
+        }
+    }
+}
+
+impl Display for Profession {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
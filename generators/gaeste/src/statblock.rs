@@ -0,0 +1,316 @@
+use std::fmt::Display;
+
+use generate::dsa5::{Eigenschaft, Erfahrungsgrad, Kampftechnik, eigenschaft_bonus};
+use rand::Rng;
+use serde::{Serialize, ser::SerializeMap};
+
+use crate::profession::Profession;
+
+/// Grundwert der Lebensenergie für Menschen
+const LEP_GRUNDWERT: u32 = 5;
+/// Seelenkraft / Zähigkeit Grundwert für Menschen
+const WIDERSTAND_GRUNDWERT: i32 = -5;
+/// Geschwindigkeit für Menschen
+const GS_GRUNDWERT: u32 = 8;
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct Waffe {
+    pub name: &'static str,
+    pub technik: Kampftechnik,
+    pub tp: &'static str,
+    pub at_mod: i32,
+    pub pa_mod: i32,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct Ruestung {
+    pub name: &'static str,
+    pub rs: u8,
+    pub be: u8,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Tradition {
+    Keine,
+    Zauberer,
+    Geweihter,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Eigenschaften([u8; 8]);
+
+impl Serialize for Eigenschaften {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(8))?;
+        for e in Eigenschaft::ALL {
+            map.serialize_entry(&e, &self.get(e))?;
+        }
+        map.end()
+    }
+}
+
+impl Eigenschaften {
+    pub fn get(&self, e: Eigenschaft) -> u8 {
+        self.0[e as usize]
+    }
+    pub fn sum(&self) -> u32 {
+        self.0.iter().map(|&e| e as u32).sum()
+    }
+}
+
+/// Kampfrelevante Werte eines NSC, wie sie in Abenteuern abgedruckt werden
+#[derive(Debug, Clone, Serialize)]
+pub struct Statblock {
+    pub erfahrungsgrad: Erfahrungsgrad,
+    pub eigenschaften: Eigenschaften,
+    pub lep: u32,
+    pub asp: Option<u32>,
+    pub kap: Option<u32>,
+    pub ini: u32,
+    pub sk: i32,
+    pub zk: i32,
+    pub aw: u32,
+    pub gs: u32,
+    pub ktw: u8,
+    pub at: u32,
+    pub pa: u32,
+    pub waffe: Waffe,
+    pub ruestung: Ruestung,
+}
+
+/// How a profession fights
+pub struct Kampfprofil {
+    /// the attributes that get boosted
+    pub primary: &'static [Eigenschaft],
+    pub waffe: Waffe,
+    pub ruestung: Ruestung,
+    pub tradition: Tradition,
+    /// how much of a fighter this profession is, 0..=3
+    pub kampfkraft: u8,
+}
+
+const fn waffe(
+    name: &'static str,
+    technik: Kampftechnik,
+    tp: &'static str,
+    at_mod: i32,
+    pa_mod: i32,
+) -> Waffe {
+    Waffe {
+        name,
+        technik,
+        tp,
+        at_mod,
+        pa_mod,
+    }
+}
+
+const KLEIDUNG: Ruestung = Ruestung {
+    name: "Normale Kleidung",
+    rs: 0,
+    be: 0,
+};
+const SCHWERE_KLEIDUNG: Ruestung = Ruestung {
+    name: "Schwere Kleidung",
+    rs: 1,
+    be: 0,
+};
+const LEDERRUESTUNG: Ruestung = Ruestung {
+    name: "Lederrüstung",
+    rs: 3,
+    be: 1,
+};
+const KETTENHEMD: Ruestung = Ruestung {
+    name: "Kettenhemd",
+    rs: 4,
+    be: 1,
+};
+
+pub fn kampfprofil(profession: Profession) -> Kampfprofil {
+    use Eigenschaft::*;
+    use Kampftechnik::*;
+    let (primary, waffe, ruestung, tradition, kampfkraft): (&[Eigenschaft], _, _, _, _) =
+        match profession {
+            Profession::Schmied => (
+                &[KK, KO, FF],
+                waffe("Schmiedehammer", Hiebwaffen, "1W6+4", 0, -1),
+                SCHWERE_KLEIDUNG,
+                Tradition::Keine,
+                1,
+            ),
+            Profession::Haendler => (
+                &[KL, CH, IN],
+                waffe("Dolch", Dolche, "1W6+1", 0, -1),
+                KLEIDUNG,
+                Tradition::Keine,
+                0,
+            ),
+            Profession::Soeldner => (
+                &[MU, GE, KK, KO],
+                waffe("Langschwert", Schwerter, "1W6+4", 0, 0),
+                KETTENHEMD,
+                Tradition::Keine,
+                3,
+            ),
+            Profession::Gelehrter => (
+                &[KL, IN],
+                waffe("Wanderstab", Stangenwaffen, "1W6+1", 0, 1),
+                KLEIDUNG,
+                Tradition::Keine,
+                0,
+            ),
+            Profession::Barde => (
+                &[CH, FF, IN],
+                waffe("Rapier", Fechtwaffen, "1W6+3", 1, 0),
+                KLEIDUNG,
+                Tradition::Keine,
+                1,
+            ),
+            Profession::Jaeger => (
+                &[IN, GE, KO],
+                waffe("Jagdspieß", Stangenwaffen, "1W6+4", 0, 0),
+                LEDERRUESTUNG,
+                Tradition::Keine,
+                2,
+            ),
+            Profession::Heiler => (
+                &[KL, IN, FF],
+                waffe("Dolch", Dolche, "1W6+1", 0, -1),
+                KLEIDUNG,
+                Tradition::Keine,
+                0,
+            ),
+            Profession::Geweihter => (
+                &[MU, IN, CH],
+                waffe("Streitkolben", Hiebwaffen, "1W6+4", 0, -1),
+                SCHWERE_KLEIDUNG,
+                Tradition::Geweihter,
+                1,
+            ),
+            Profession::Magier => (
+                &[KL, IN, CH],
+                waffe("Magierstab", Stangenwaffen, "1W6+2", 0, -1),
+                KLEIDUNG,
+                Tradition::Zauberer,
+                0,
+            ),
+            Profession::Dieb => (
+                &[GE, FF, IN],
+                waffe("Dolch", Dolche, "1W6+1", 0, -1),
+                KLEIDUNG,
+                Tradition::Keine,
+                1,
+            ),
+            Profession::Seefahrer => (
+                &[GE, KO, KK],
+                waffe("Säbel", Schwerter, "1W6+3", 0, 0),
+                SCHWERE_KLEIDUNG,
+                Tradition::Keine,
+                2,
+            ),
+            Profession::Bauer => (
+                &[KO, KK],
+                waffe("Dreschflegel", Hiebwaffen, "1W6+3", 0, -2),
+                KLEIDUNG,
+                Tradition::Keine,
+                0,
+            ),
+        };
+    Kampfprofil {
+        primary,
+        waffe,
+        ruestung,
+        tradition,
+        kampfkraft,
+    }
+}
+
+impl Statblock {
+    pub fn generate(rng: &mut impl Rng, profession: Profession, grad: Erfahrungsgrad) -> Self {
+        let profil = kampfprofil(profession);
+        let max = grad.max_eigenschaft();
+        let mut werte = [0u8; 8];
+        for e in Eigenschaft::ALL {
+            let boost = if profil.primary.contains(&e) { 2 } else { 0 };
+            werte[e as usize] = (rng.random_range(9..=11) + boost + grad as u8 / 2).min(max);
+        }
+        let eig = Eigenschaften(werte);
+        let get = |e| eig.get(e);
+
+        let ktw =
+            (6 + 2 * profil.kampfkraft + grad as u8 + rng.random_range(0..=2)).min(grad.max_ktw());
+        let leit = profil
+            .waffe
+            .technik
+            .leiteigenschaften()
+            .iter()
+            .map(|&e| get(e))
+            .max()
+            .unwrap_or(8);
+        // Belastung durch die Rüstung senkt AT, PA, INI und GS
+        let be = profil.ruestung.be;
+        let at =
+            ktw as i32 + eigenschaft_bonus(get(Eigenschaft::MU)) + profil.waffe.at_mod - be as i32;
+        let pa = ktw.div_ceil(2) as i32 + eigenschaft_bonus(leit) + profil.waffe.pa_mod - be as i32;
+        let (asp, kap) = match profil.tradition {
+            Tradition::Keine => (None, None),
+            Tradition::Zauberer => (Some(20 + get(Eigenschaft::KL) as u32), None),
+            Tradition::Geweihter => (None, Some(20 + get(Eigenschaft::IN) as u32)),
+        };
+        Self {
+            erfahrungsgrad: grad,
+            eigenschaften: eig,
+            lep: LEP_GRUNDWERT + 2 * get(Eigenschaft::KO) as u32,
+            asp,
+            kap,
+            ini: ((get(Eigenschaft::MU) as u32 + get(Eigenschaft::GE) as u32) / 2)
+                .saturating_sub(be as u32),
+            sk: WIDERSTAND_GRUNDWERT
+                + (get(Eigenschaft::MU) + get(Eigenschaft::KL) + get(Eigenschaft::IN)) as i32 / 6,
+            zk: WIDERSTAND_GRUNDWERT + (2 * get(Eigenschaft::KO) + get(Eigenschaft::KK)) as i32 / 6,
+            aw: get(Eigenschaft::GE) as u32 / 2,
+            gs: GS_GRUNDWERT.saturating_sub(be as u32),
+            ktw,
+            at: at.max(0) as u32,
+            pa: pa.max(0) as u32,
+            waffe: profil.waffe,
+            ruestung: profil.ruestung,
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("statblock is always serializable")
+    }
+}
+
+impl Display for Statblock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let eig: Vec<String> = Eigenschaft::ALL
+            .iter()
+            .map(|&e| format!("{e} {}", self.eigenschaften.get(e)))
+            .collect();
+        writeln!(f, "{} ({})", eig.join(" "), self.erfahrungsgrad)?;
+        write!(f, "LeP {}", self.lep)?;
+        if let Some(asp) = self.asp {
+            write!(f, " AsP {asp}")?;
+        }
+        if let Some(kap) = self.kap {
+            write!(f, " KaP {kap}")?;
+        }
+        writeln!(
+            f,
+            " INI {}+1W6 AW {} SK {} ZK {} GS {}",
+            self.ini, self.aw, self.sk, self.zk, self.gs
+        )?;
+        writeln!(
+            f,
+            "{} ({} {}): AT {} PA {} TP {}",
+            self.waffe.name, self.waffe.technik, self.ktw, self.at, self.pa, self.waffe.tp
+        )?;
+        write!(
+            f,
+            "{}: RS/BE {}/{}",
+            self.ruestung.name, self.ruestung.rs, self.ruestung.be
+        )
+    }
+}
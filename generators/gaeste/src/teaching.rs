@@ -0,0 +1,101 @@
+use std::fmt::Display;
+
+use generate::dsa5::{Steigerungsfaktor, Talent};
+use schatzi::money::Money;
+
+/// hours a single lesson takes
+const LESSON_HOURS: u32 = 2;
+/// nobody teaches more than this per day
+const TEACHING_HOURS_PER_DAY: u32 = 8;
+/// a teacher needs at least this FW to be worth paying
+pub const MIN_TEACHING_FW: u8 = 7;
+
+/// An offer to teach a talent.
+/// Following DSA5 a teacher can raise a student's FW up to their own FW.
+/// Every AP the student spends is one lesson, so the total number of lessons
+/// depends on the talent's Steigerungsfaktor and the FW to be reached.
+#[derive(Debug, Clone, Copy)]
+pub struct TeachingOffer {
+    pub talent: Talent,
+    pub max_fw: u8,
+    pub category: Steigerungsfaktor,
+    pub price_per_lesson: Money,
+    pub lesson_hours: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TeachingCost {
+    pub lessons: u32,
+    pub price: Money,
+    pub hours: u32,
+}
+
+impl TeachingCost {
+    /// full teaching days needed, the last one may be partial
+    pub fn days(&self) -> u32 {
+        self.hours.div_ceil(TEACHING_HOURS_PER_DAY)
+    }
+}
+
+impl TeachingOffer {
+    pub fn new(talent: Talent, teacher_fw: u8) -> Self {
+        let category = talent.steigerungsfaktor();
+        // better teachers and harder talents are more expensive
+        let heller = category.base_cost() + teacher_fw as u32 / 3;
+        Self {
+            talent,
+            max_fw: teacher_fw,
+            category,
+            price_per_lesson: Money::from_heller(heller),
+            lesson_hours: LESSON_HOURS,
+        }
+    }
+
+    /// cost for raising the student's FW from `from` to `to`,
+    /// a lesson conveys exactly one AP worth of training.
+    /// None if the teacher cannot teach up to `to`.
+    pub fn cost(&self, from: u8, to: u8) -> Option<TeachingCost> {
+        if to > self.max_fw || to <= from {
+            return None;
+        }
+        let lessons = self.category.cost(from, to);
+        Some(TeachingCost {
+            lessons,
+            price: self.price_per_lesson * lessons,
+            hours: self.lesson_hours * lessons,
+        })
+    }
+}
+
+impl Display for TeachingOffer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} ({}) bis FW {}: {} pro Lektion à {} Std.",
+            self.talent, self.category, self.max_fw, self.price_per_lesson, self.lesson_hours
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use generate::dsa5::Talent;
+
+    use super::TeachingOffer;
+
+    #[test]
+    fn teaching_cost() {
+        // Klettern is B, 6 H per lesson at FW 12
+        let offer = TeachingOffer::new(Talent::Klettern, 12);
+        assert_eq!(offer.price_per_lesson.as_kreuzer(), 60);
+
+        let cost = offer.cost(10, 12).unwrap();
+        assert_eq!(cost.lessons, 4);
+        assert_eq!(cost.price.as_kreuzer(), 240);
+        assert_eq!(cost.hours, 8);
+        assert_eq!(cost.days(), 1);
+
+        assert!(offer.cost(12, 13).is_none());
+        assert!(offer.cost(5, 5).is_none());
+    }
+}
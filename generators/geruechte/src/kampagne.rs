@@ -0,0 +1,87 @@
+use std::{fmt::Display, path::Path};
+
+use serde::Deserialize;
+
+/// A fact about the campaign world. Rumours built from facts are either
+/// repeated faithfully or distorted by swapping the subject or the statement.
+///
+/// ```toml
+/// [[fakt]]
+/// subjekt = "Vogt Answin von Ferdok"
+/// aussage = "ist beim Phextempel hoch verschuldet"
+/// ort = "Ferdok"
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct Fakt {
+    pub subjekt: String,
+    pub aussage: String,
+    pub ort: Option<String>,
+}
+
+/// Everything a campaign file can contain. All lists are optional,
+/// generated names, places and items are used where a list is empty.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Kampagne {
+    #[serde(default, rename = "fakt")]
+    pub fakten: Vec<Fakt>,
+    #[serde(default)]
+    pub personen: Vec<String>,
+    #[serde(default)]
+    pub orte: Vec<String>,
+    /// im Akkusativ, "einen Dolch" statt "ein Dolch"
+    #[serde(default)]
+    pub gegenstaende: Vec<String>,
+}
+
+#[derive(Debug)]
+pub enum KampagneError {
+    Io(std::io::Error),
+    Parse(toml::de::Error),
+}
+
+impl Display for KampagneError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "Kampagnendatei konnte nicht gelesen werden: {e}"),
+            Self::Parse(e) => write!(f, "Kampagnendatei ist fehlerhaft: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for KampagneError {}
+
+impl Kampagne {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, KampagneError> {
+        let content = std::fs::read_to_string(path).map_err(KampagneError::Io)?;
+        content.parse()
+    }
+
+    /// every person the campaign knows about, including fact subjects
+    pub fn alle_personen(&self) -> Vec<&str> {
+        let mut personen: Vec<&str> = self.personen.iter().map(String::as_str).collect();
+        for f in &self.fakten {
+            if !personen.contains(&f.subjekt.as_str()) {
+                personen.push(&f.subjekt);
+            }
+        }
+        personen
+    }
+
+    /// every place the campaign knows about, including fact locations
+    pub fn alle_orte(&self) -> Vec<&str> {
+        let mut orte: Vec<&str> = self.orte.iter().map(String::as_str).collect();
+        for ort in self.fakten.iter().filter_map(|f| f.ort.as_deref()) {
+            if !orte.contains(&ort) {
+                orte.push(ort);
+            }
+        }
+        orte
+    }
+}
+
+impl std::str::FromStr for Kampagne {
+    type Err = KampagneError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        toml::from_str(s).map_err(KampagneError::Parse)
+    }
+}
@@ -0,0 +1,216 @@
+use std::fmt::Display;
+
+use gaeste::{Configuration, Gaestegenerator};
+use generate::RPGenerator;
+use rand::{Rng, SeedableRng, rngs::StdRng, seq::IndexedRandom};
+
+use crate::kampagne::{Fakt, Kampagne};
+
+pub mod kampagne;
+mod vorlagen;
+
+/// how often a rumour is built from a campaign fact if there are any
+const FAKT_ANTEIL: f64 = 0.6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Wahrheitsgehalt {
+    Wahr,
+    Halbwahr,
+    Falsch,
+}
+
+impl Display for Wahrheitsgehalt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::Wahr => "wahr",
+            Self::Halbwahr => "halbwahr",
+            Self::Falsch => "falsch",
+        };
+        write!(f, "{s}")
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Geruecht {
+    pub text: String,
+    pub wahrheit: Wahrheitsgehalt,
+    /// who tells it, name and profession
+    pub verbreiter: String,
+    /// index of the campaign fact this rumour is based on
+    pub fakt: Option<usize>,
+}
+
+pub struct Geruechtegenerator {
+    rng: StdRng,
+    gaeste: Gaestegenerator,
+    kampagne: Kampagne,
+}
+
+impl Geruechtegenerator {
+    pub fn new(kampagne: Kampagne) -> Self {
+        Self {
+            rng: StdRng::from_os_rng(),
+            gaeste: Gaestegenerator::new(Configuration::default()),
+            kampagne,
+        }
+    }
+
+    fn generated_name(&mut self) -> String {
+        self.gaeste.next().map(|g| g.name).unwrap_or_default()
+    }
+
+    /// picks a campaign entry half of the time (if there are any) and a generated one otherwise
+    fn pick(&mut self, campaign: Vec<String>, fallback: &[&str]) -> String {
+        match campaign.choose(&mut self.rng) {
+            Some(entry) if self.rng.random_bool(0.5) => entry.clone(),
+            _ => fallback.choose(&mut self.rng).unwrap().to_string(),
+        }
+    }
+
+    fn wahrheit(&mut self, weights: [u32; 3]) -> Wahrheitsgehalt {
+        let options = [
+            (Wahrheitsgehalt::Wahr, weights[0]),
+            (Wahrheitsgehalt::Halbwahr, weights[1]),
+            (Wahrheitsgehalt::Falsch, weights[2]),
+        ];
+        options.choose_weighted(&mut self.rng, |x| x.1).unwrap().0
+    }
+
+    fn aus_fakt(&mut self, idx: usize) -> (String, Wahrheitsgehalt) {
+        let Fakt {
+            subjekt, aussage, ..
+        } = self.kampagne.fakten[idx].clone();
+        let wahrheit = self.wahrheit([4, 3, 3]);
+        let text = match wahrheit {
+            Wahrheitsgehalt::Wahr => format!("{subjekt} {aussage}."),
+            Wahrheitsgehalt::Halbwahr => {
+                let extra = vorlagen::UEBERTREIBUNGEN.choose(&mut self.rng).unwrap();
+                format!("{subjekt} {aussage}, {extra}.")
+            }
+            Wahrheitsgehalt::Falsch => {
+                // the statement is true, but about somebody else
+                let andere: Vec<String> = self
+                    .kampagne
+                    .alle_personen()
+                    .into_iter()
+                    .filter(|&p| p != subjekt)
+                    .map(String::from)
+                    .collect();
+                let anderer = match andere.choose(&mut self.rng) {
+                    Some(p) => p.clone(),
+                    None => self.generated_name(),
+                };
+                format!("{anderer} {aussage}.")
+            }
+        };
+        (text, wahrheit)
+    }
+
+    fn aus_vorlage(&mut self) -> (String, Wahrheitsgehalt) {
+        let vorlage = *vorlagen::VORLAGEN.choose(&mut self.rng).unwrap();
+        let personen = owned(self.kampagne.alle_personen());
+        let person = match personen.choose(&mut self.rng) {
+            Some(p) if self.rng.random_bool(0.5) => p.clone(),
+            _ => self.generated_name(),
+        };
+        let ort = self.pick(owned(self.kampagne.alle_orte()), vorlagen::ORTE);
+        let gegenstand = self.pick(self.kampagne.gegenstaende.clone(), vorlagen::GEGENSTAENDE);
+
+        let text = vorlage
+            .replace("{person}", &person)
+            .replace("{ort}", &ort)
+            .replace("{gegenstand}", &gegenstand);
+        // invented rumours are mostly nonsense
+        (text, self.wahrheit([2, 3, 5]))
+    }
+}
+
+fn owned(v: Vec<&str>) -> Vec<String> {
+    v.into_iter().map(String::from).collect()
+}
+
+impl Iterator for Geruechtegenerator {
+    type Item = Geruecht;
+    fn next(&mut self) -> Option<Self::Item> {
+        let fakt = if !self.kampagne.fakten.is_empty() && self.rng.random_bool(FAKT_ANTEIL) {
+            Some(self.rng.random_range(0..self.kampagne.fakten.len()))
+        } else {
+            None
+        };
+        let (text, wahrheit) = match fakt {
+            Some(idx) => self.aus_fakt(idx),
+            None => self.aus_vorlage(),
+        };
+        let verbreiter = self
+            .gaeste
+            .next()
+            .map(|g| format!("{} ({})", g.name, g.profession))?;
+        Some(Geruecht {
+            text,
+            wahrheit,
+            verbreiter,
+            fakt,
+        })
+    }
+}
+
+impl RPGenerator for Geruechtegenerator {
+    type Seed = u64;
+    fn seed(&mut self, s: Self::Seed) {
+        self.rng = StdRng::seed_from_u64(s);
+        self.gaeste.seed(s.wrapping_add(1));
+    }
+}
+
+impl Display for Geruecht {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "\"{}\" erzählt {} [{}]",
+            self.text, self.verbreiter, self.wahrheit
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use generate::RPGenerator;
+
+    use crate::{Geruechtegenerator, Wahrheitsgehalt, kampagne::Kampagne};
+
+    const KAMPAGNE: &str = r#"
+personen = ["Alrik der Schmied"]
+orte = ["Ferdok"]
+
+[[fakt]]
+subjekt = "Vogt Answin"
+aussage = "ist beim Phextempel hoch verschuldet"
+ort = "Ferdok"
+"#;
+
+    #[test]
+    fn rumours_from_facts() {
+        let kampagne: Kampagne = KAMPAGNE.parse().unwrap();
+        assert_eq!(
+            kampagne.alle_personen(),
+            ["Alrik der Schmied", "Vogt Answin"]
+        );
+
+        let mut generator = Geruechtegenerator::new(kampagne);
+        generator.seed(1);
+        let rumours: Vec<_> = generator.take(100).collect();
+        assert!(rumours.iter().any(|r| r.fakt.is_some()));
+        for r in rumours.iter().filter(|r| r.fakt.is_some()) {
+            let names_vogt = r.text.starts_with("Vogt Answin");
+            match r.wahrheit {
+                Wahrheitsgehalt::Wahr | Wahrheitsgehalt::Halbwahr => assert!(names_vogt),
+                Wahrheitsgehalt::Falsch => assert!(!names_vogt),
+            }
+        }
+    }
+
+    #[test]
+    fn broken_campaign_file() {
+        assert!("[[fakt]]\nsubjekt = 3".parse::<Kampagne>().is_err());
+    }
+}
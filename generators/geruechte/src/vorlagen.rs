@@ -0,0 +1,64 @@
+//! Vorlagen für erfundene Gerüchte.
+//! Platzhalter: `{person}`, `{ort}` und `{gegenstand}`, jeweils höchstens einmal pro Vorlage.
+//! Orte stehen im Dativ, Gegenstände im Akkusativ, die Vorlagen müssen dazu passen.
+
+pub const VORLAGEN: &[&str] = &[
+    "{person} soll in {ort} {gegenstand} gestohlen haben.",
+    "In {ort} spukt es, seit {person} dort verschwunden ist.",
+    "{person} sucht händeringend {gegenstand} und zahlt gut dafür.",
+    "Man munkelt, {person} sei ein Spion der Horasischen Krone.",
+    "In {ort} soll jemand {gegenstand} vergraben haben.",
+    "{person} wurde nachts bei {ort} gesehen, {gegenstand} unter dem Arm.",
+    "Die Wachen in {ort} lassen sich neuerdings bestechen.",
+    "{person} hat beim Boltan in {ort} alles verloren.",
+    "Wer {gegenstand} besitzt, dem soll kein Unglück widerfahren, sagt {person}.",
+    "In {ort} treiben sich Orks herum, angeblich hat {person} sie gesehen.",
+];
+
+pub const ORTE: &[&str] = &[
+    "der alten Mühle",
+    "dem Gasthaus zum Goldenen Hirschen",
+    "den Katakomben unter dem Borontempel",
+    "der Ruine am Schwarzen See",
+    "dem Hafenviertel",
+    "dem Gut derer von Rabenmund",
+    "der Schmiede am Markt",
+];
+
+pub const GEGENSTAENDE: &[&str] = &[
+    "ein Amulett von Phex",
+    "eine Truhe voller Dukaten",
+    "ein Schwert aus Zwergenstahl",
+    "ein Buch mit verbotenen Zaubern",
+    "eine Karte zu einem Drachenhort",
+    "den Siegelring des Barons",
+    "ein Fass Premer Feuer",
+];
+
+/// Übertreibungen, mit denen wahre Fakten halbwahr werden
+pub const UEBERTREIBUNGEN: &[&str] = &[
+    "und das schon seit Jahren",
+    "und mindestens drei andere wissen davon",
+    "und die Praioten sind schon unterwegs",
+    "aber das ist noch längst nicht alles",
+];
+
+#[cfg(test)]
+mod test {
+    use super::VORLAGEN;
+
+    #[test]
+    fn gegenstaende_im_akkusativ() {
+        // "nach einen Dolch" klingt nicht nach Taverne
+        for vorlage in VORLAGEN {
+            let Some((davor, _)) = vorlage.split_once("{gegenstand}") else {
+                continue;
+            };
+            let wort = davor.split_whitespace().last().unwrap_or_default();
+            assert!(
+                !["nach", "mit", "von", "zu", "aus", "bei", "seit"].contains(&wort),
+                "{vorlage}"
+            );
+        }
+    }
+}
@@ -0,0 +1,205 @@
+use std::fmt::Display;
+
+use gaeste::profession::Profession;
+use generate::RPGenerator;
+use rand::{
+    Rng, SeedableRng,
+    rngs::StdRng,
+    seq::{IndexedRandom, IteratorRandom},
+};
+use schatzi::Stratum;
+
+mod tables;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Kultur {
+    Mittelreich,
+    Horasreich,
+    Thorwal,
+    Tulamidenlande,
+    Bornland,
+    Nostria,
+}
+
+impl Kultur {
+    pub const ALL: [Kultur; 6] = [
+        Self::Mittelreich,
+        Self::Horasreich,
+        Self::Thorwal,
+        Self::Tulamidenlande,
+        Self::Bornland,
+        Self::Nostria,
+    ];
+}
+
+impl Display for Kultur {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::Mittelreich => "dem Mittelreich",
+            Self::Horasreich => "dem Horasreich",
+            Self::Thorwal => "Thorwal",
+            Self::Tulamidenlande => "den Tulamidenlanden",
+            Self::Bornland => "dem Bornland",
+            Self::Nostria => "Nostria/Andergast",
+        };
+        write!(f, "{s}")
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Ereignis {
+    pub alter: u8,
+    pub beschreibung: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct Lebensgeschichte {
+    pub kultur: Kultur,
+    pub stratum: Stratum,
+    pub geburtsort: &'static str,
+    pub familie: &'static str,
+    pub profession: Profession,
+    pub alter: u8,
+    /// chronologically ordered
+    pub ereignisse: Vec<Ereignis>,
+}
+
+#[derive(Debug, Default)]
+pub struct Configuration {
+    /// random if None
+    pub kultur: Option<Kultur>,
+    /// random if None
+    pub stratum: Option<Stratum>,
+}
+
+pub struct Lebensgeschichtengenerator {
+    rng: StdRng,
+    config: Configuration,
+}
+
+impl Lebensgeschichtengenerator {
+    pub fn new(config: Configuration) -> Self {
+        Self {
+            rng: StdRng::from_os_rng(),
+            config,
+        }
+    }
+
+    fn random_stratum(&mut self) -> Stratum {
+        // most people are neither rich nor beggars
+        *[
+            (Stratum::Rich, 1),
+            (Stratum::Normal, 4),
+            (Stratum::Poor, 4),
+            (Stratum::Beggar, 1),
+        ]
+        .choose_weighted(&mut self.rng, |x| x.1)
+        .map(|x| &x.0)
+        .unwrap()
+    }
+
+    fn ereignis(&mut self, ages: std::ops::RangeInclusive<u8>, table: &[&str]) -> Option<Ereignis> {
+        Some(Ereignis {
+            alter: self.rng.random_range(ages),
+            beschreibung: table.choose(&mut self.rng)?.to_string(),
+        })
+    }
+}
+
+impl Iterator for Lebensgeschichtengenerator {
+    type Item = Lebensgeschichte;
+    fn next(&mut self) -> Option<Self::Item> {
+        let kultur = match self.config.kultur {
+            Some(k) => k,
+            None => *Kultur::ALL.choose(&mut self.rng)?,
+        };
+        let stratum = match self.config.stratum {
+            Some(s) => s,
+            None => self.random_stratum(),
+        };
+        let profession = *Profession::ALL
+            .choose_weighted(&mut self.rng, |&p| tables::professions_gewicht(p, stratum))
+            .ok()?;
+        let alter = self.rng.random_range(18..=50);
+        let geburtsort = *tables::geburtsorte(kultur).choose(&mut self.rng)?;
+        let familie = *tables::familie(stratum).choose(&mut self.rng)?;
+
+        let mut ereignisse = vec![Ereignis {
+            alter: 0,
+            beschreibung: format!("geboren in {geburtsort} als {familie}"),
+        }];
+        for _ in 0..self.rng.random_range(1..=2) {
+            ereignisse.extend(self.ereignis(5..=12, tables::kindheit(stratum)));
+        }
+        ereignisse.extend(self.ereignis(13..=16, tables::jugend(kultur)));
+        ereignisse.push(Ereignis {
+            alter: self.rng.random_range(14..=17),
+            beschreibung: tables::ausbildung(profession, stratum).to_string(),
+        });
+        // roughly one notable deed every eight years of adulthood
+        let tat_count = (alter as usize - 18) / 8 + self.rng.random_range(0..=1);
+        let taten = tables::taten()
+            .iter()
+            .choose_multiple(&mut self.rng, tat_count);
+        for tat in taten {
+            ereignisse.push(Ereignis {
+                alter: self.rng.random_range(18..=alter),
+                beschreibung: tat.to_string(),
+            });
+        }
+        ereignisse.sort_by_key(|e| e.alter);
+
+        Some(Lebensgeschichte {
+            kultur,
+            stratum,
+            geburtsort,
+            familie,
+            profession,
+            alter,
+            ereignisse,
+        })
+    }
+}
+
+impl RPGenerator for Lebensgeschichtengenerator {
+    type Seed = u64;
+    fn seed(&mut self, s: Self::Seed) {
+        self.rng = StdRng::seed_from_u64(s);
+    }
+}
+
+impl Display for Lebensgeschichte {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{} aus {}, {} Jahre alt, {}",
+            self.profession, self.kultur, self.alter, self.stratum
+        )?;
+        for e in &self.ereignisse {
+            writeln!(f, "{:>3}: {}", e.alter, e.beschreibung)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use generate::RPGenerator;
+    use schatzi::Stratum;
+
+    use crate::{Configuration, Lebensgeschichtengenerator};
+
+    #[test]
+    fn chronological() {
+        let mut generator = Lebensgeschichtengenerator::new(Configuration {
+            kultur: None,
+            stratum: Some(Stratum::Beggar),
+        });
+        generator.seed(42);
+        for story in generator.take(50) {
+            assert_eq!(story.stratum, Stratum::Beggar);
+            assert!(story.ereignisse.is_sorted_by_key(|e| e.alter));
+            assert!(story.ereignisse.iter().all(|e| e.alter <= story.alter));
+        }
+    }
+}
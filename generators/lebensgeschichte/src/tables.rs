@@ -0,0 +1,198 @@
+use gaeste::profession::Profession;
+use schatzi::Stratum;
+
+use crate::Kultur;
+
+pub fn geburtsorte(kultur: Kultur) -> &'static [&'static str] {
+    match kultur {
+        Kultur::Mittelreich => &[
+            "Gareth",
+            "Ferdok",
+            "Angbar",
+            "Greifenfurt",
+            "Wehrheim",
+            "einem Dorf in der Rommilyser Mark",
+            "einem Weiler im Kosch",
+        ],
+        Kultur::Horasreich => &[
+            "Vinsalt",
+            "Kuslik",
+            "Grangor",
+            "Methumis",
+            "Bethana",
+            "einem Weingut im Yaquirtal",
+        ],
+        Kultur::Thorwal => &[
+            "Thorwal",
+            "Prem",
+            "Olport",
+            "Varnheim",
+            "einer Ottaskin an der Küste",
+        ],
+        Kultur::Tulamidenlande => &[
+            "Fasar",
+            "Khunchom",
+            "Mherwed",
+            "Rashdul",
+            "einer Oase in der Khôm",
+        ],
+        Kultur::Bornland => &[
+            "Festum",
+            "Norburg",
+            "Neersand",
+            "einem Leibeigenendorf an der Walsach",
+        ],
+        Kultur::Nostria => &[
+            "Nostria",
+            "Andergast",
+            "Salza",
+            "einem Köhlerdorf im Steineichenwald",
+        ],
+    }
+}
+
+pub fn familie(stratum: Stratum) -> &'static [&'static str] {
+    match stratum {
+        Stratum::Rich => &[
+            "Kind eines Handelsherrn mit eigenem Kontor",
+            "jüngstes Kind einer verarmenden Adelsfamilie",
+            "Kind eines Zunftmeisters mit Sitz im Stadtrat",
+            "Erbe eines Gutshofes mit zwanzig Knechten",
+        ],
+        Stratum::Normal => &[
+            "Kind eines Handwerkers",
+            "Kind einer Schankwirtsfamilie",
+            "Kind freier Bauern",
+            "Kind eines Stadtgardisten",
+        ],
+        Stratum::Poor => &[
+            "Kind eines Tagelöhners",
+            "Kind leibeigener Bauern",
+            "eines von neun Kindern einer Wäscherin",
+            "Kind eines Fischers ohne eigenes Boot",
+        ],
+        Stratum::Beggar => &[
+            "Findelkind, das vor einem Traviatempel abgelegt wurde",
+            "Kind von Bettlern aus dem Armenviertel",
+            "Waise, aufgewachsen bei fahrendem Volk",
+            "Kind einer Diebesfamilie",
+        ],
+    }
+}
+
+/// Ereignisse in der Kindheit, die von der Schicht abhängen
+pub fn kindheit(stratum: Stratum) -> &'static [&'static str] {
+    match stratum {
+        Stratum::Rich => &[
+            "erhielt Unterricht von einem Hauslehrer",
+            "wurde auf eine Reise in die Hauptstadt mitgenommen",
+            "verlor ein Geschwister an das Blaue Keuchen",
+            "wurde mit einem Kind einer befreundeten Familie verlobt",
+        ],
+        Stratum::Normal => &[
+            "lernte bei einem Wanderpriester lesen und schreiben",
+            "half bei der Ernte, als die Heuschrecken kamen",
+            "erlebte den Brand des Elternhauses",
+            "fand im Wald ein verletztes Tier und pflegte es gesund",
+        ],
+        Stratum::Poor => &[
+            "musste früh für die Familie mitarbeiten",
+            "überlebte einen harten Winter mit knapper Not",
+            "wurde beim Stehlen von Brot erwischt und ausgepeitscht",
+            "verlor einen Elternteil bei einem Unfall",
+        ],
+        Stratum::Beggar => &[
+            "schlug sich allein auf der Straße durch",
+            "wurde von einer Diebesbande aufgenommen",
+            "entging knapp den Häschern eines Sklavenhändlers",
+            "lebte einen Winter lang in einem verlassenen Stall",
+        ],
+    }
+}
+
+/// Ereignisse in der Jugend, die von der Kultur abhängen
+pub fn jugend(kultur: Kultur) -> &'static [&'static str] {
+    match kultur {
+        Kultur::Mittelreich => &[
+            "sah einen Zug der Kaiserlichen Armee vorbeiziehen",
+            "erlebte die Hexenverfolgungen eines übereifrigen Praiosgeweihten",
+            "gewann beim Tjost auf dem Jahrmarkt einen Preis",
+        ],
+        Kultur::Horasreich => &[
+            "erlebte einen Maskenball mit und verlor dort das Herz",
+            "wurde in eine Intrige zweier Adelshäuser verwickelt",
+            "besuchte die Theater und Lesezirkel der Stadt",
+        ],
+        Kultur::Thorwal => &[
+            "fuhr das erste Mal auf einer Otta mit",
+            "trat einem Hjaldingard bei",
+            "überstand einen Sturm auf der See der Sieben Winde",
+        ],
+        Kultur::Tulamidenlande => &[
+            "zog mit einer Karawane durch die Wüste",
+            "wurde Zeuge eines Duells zweier Magier",
+            "lernte auf dem Basar das Feilschen",
+        ],
+        Kultur::Bornland => &[
+            "musste beim Bronnjaren Frondienst leisten",
+            "erlebte einen Goblinüberfall",
+            "überstand eine Hungersnot nach einer Missernte",
+        ],
+        Kultur::Nostria => &[
+            "wurde in den ewigen Krieg zwischen Nostria und Andergast hineingezogen",
+            "begegnete einer Hexe im Wald",
+            "half beim Flößen der Steineichen",
+        ],
+    }
+}
+
+pub fn ausbildung(profession: Profession, stratum: Stratum) -> &'static str {
+    let rich = matches!(stratum, Stratum::Rich | Stratum::Normal);
+    match (profession, rich) {
+        (Profession::Schmied, _) => "ging bei einem Schmiedemeister in die Lehre",
+        (Profession::Haendler, true) => "arbeitete als Schreiber im Kontor eines Handelshauses",
+        (Profession::Haendler, false) => "zog als Hausierer über die Dörfer",
+        (Profession::Soeldner, _) => "ließ sich von einem Söldnerhaufen anwerben",
+        (Profession::Gelehrter, true) => "studierte an einer Universität",
+        (Profession::Gelehrter, false) => "wurde als Kopist in einem Hesindetempel aufgenommen",
+        (Profession::Barde, _) => "zog mit einer Gauklertruppe umher",
+        (Profession::Jaeger, _) => "lernte bei einem alten Waidmann das Jagen",
+        (Profession::Heiler, _) => "ging bei einem Bader in die Lehre",
+        (Profession::Geweihter, _) => "wurde als Novize in einen Tempel aufgenommen",
+        (Profession::Magier, true) => "wurde an einer Magierakademie ausgebildet",
+        (Profession::Magier, false) => "wurde von einem Wandermagier als Lehrling angenommen",
+        (Profession::Dieb, _) => "wurde in einer Diebesgilde ausgebildet",
+        (Profession::Seefahrer, _) => "heuerte als Schiffsjunge an",
+        (Profession::Bauer, _) => "übernahm den Hof der Familie",
+    }
+}
+
+/// Wie wahrscheinlich (relativ) eine Profession für eine Schicht ist
+pub fn professions_gewicht(profession: Profession, stratum: Stratum) -> u32 {
+    use Profession::*;
+    match (stratum, profession) {
+        (Stratum::Rich, Gelehrter | Magier | Haendler) => 5,
+        (Stratum::Rich, Dieb | Bauer) => 0,
+        (Stratum::Normal, Schmied | Haendler | Heiler | Bauer) => 4,
+        (Stratum::Poor, Bauer | Soeldner | Seefahrer | Jaeger) => 4,
+        (Stratum::Poor | Stratum::Beggar, Magier | Gelehrter) => 1,
+        (Stratum::Beggar, Dieb | Soeldner | Barde) => 5,
+        (Stratum::Beggar, Haendler | Schmied) => 0,
+        _ => 2,
+    }
+}
+
+pub fn taten() -> &'static [&'static str] {
+    &[
+        "rettete ein Kind aus einem brennenden Haus",
+        "erschlug einen Oger, der ein Dorf terrorisierte",
+        "verlor ein Vermögen beim Boltan",
+        "deckte die Machenschaften eines korrupten Vogts auf",
+        "überlebte als Einziger einen Schiffbruch",
+        "wurde für eine Tat verurteilt, die jemand anders begangen hatte",
+        "begleitete eine Pilgerfahrt nach Gareth",
+        "fand in einer Ruine ein altes Artefakt",
+        "geriet in Gefangenschaft und konnte fliehen",
+        "gewann die Gunst eines Adligen",
+    ]
+}
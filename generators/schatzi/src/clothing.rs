@@ -0,0 +1,464 @@
+//! What a person wears, by stratum, region and season.
+
+use std::fmt::Display;
+
+use rand::{Rng, seq::IndexedRandom};
+
+use crate::{Stratum, money::Money};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Season {
+    Spring,
+    #[default]
+    Summer,
+    Autumn,
+    Winter,
+}
+
+impl Season {
+    pub const ALL: [Season; 4] = [Self::Spring, Self::Summer, Self::Autumn, Self::Winter];
+
+    /// chance that someone wears a cloak or coat
+    fn cloak_chance(self, climate: Climate) -> f64 {
+        match (climate, self) {
+            (Climate::Cold, Self::Summer) => 0.5,
+            (Climate::Cold, _) => 1.0,
+            (Climate::Temperate, Self::Summer) => 0.1,
+            (Climate::Temperate, Self::Spring | Self::Autumn) => 0.5,
+            (Climate::Temperate, Self::Winter) => 1.0,
+            (Climate::Hot, Self::Winter) => 0.5,
+            (Climate::Hot, _) => 0.1,
+        }
+    }
+
+    /// whether anyone would wear fur
+    fn fur(self, climate: Climate) -> bool {
+        match climate {
+            Climate::Cold => self != Self::Summer,
+            Climate::Temperate => matches!(self, Self::Autumn | Self::Winter),
+            Climate::Hot => false,
+        }
+    }
+}
+
+impl Display for Season {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::Spring => "Frühling",
+            Self::Summer => "Sommer",
+            Self::Autumn => "Herbst",
+            Self::Winter => "Winter",
+        };
+        write!(f, "{s}")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Climate {
+    Cold,
+    Temperate,
+    Hot,
+}
+
+/// Where someone lives decides the fashion and how warm they dress,
+/// independent of the coins they pay with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Region {
+    #[default]
+    Mittelreich,
+    Horasreich,
+    /// Nostria and Andergast
+    Nostria,
+    Thorwal,
+    Bornland,
+    Tulamidenlande,
+    AlAnfa,
+}
+
+impl Region {
+    pub const ALL: [Region; 7] = [
+        Self::Mittelreich,
+        Self::Horasreich,
+        Self::Nostria,
+        Self::Thorwal,
+        Self::Bornland,
+        Self::Tulamidenlande,
+        Self::AlAnfa,
+    ];
+
+    fn climate(self) -> Climate {
+        match self {
+            Self::Nostria | Self::Thorwal | Self::Bornland => Climate::Cold,
+            Self::Mittelreich | Self::Horasreich => Climate::Temperate,
+            Self::Tulamidenlande | Self::AlAnfa => Climate::Hot,
+        }
+    }
+}
+
+impl Display for Region {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::Mittelreich => "Mittelreich",
+            Self::Horasreich => "Horasreich",
+            Self::Nostria => "Nostria und Andergast",
+            Self::Thorwal => "Thorwal",
+            Self::Bornland => "Bornland",
+            Self::Tulamidenlande => "Tulamidenlande",
+            Self::AlAnfa => "Al'Anfa",
+        };
+        write!(f, "{s}")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Material {
+    Sackleinen,
+    Leinen,
+    Wolle,
+    Baumwolle,
+    Leder,
+    Samt,
+    Seide,
+    Pelz,
+}
+
+impl Material {
+    /// price in percent of a linen garment
+    fn percent(self) -> u64 {
+        match self {
+            Self::Sackleinen => 20,
+            Self::Leinen => 100,
+            Self::Wolle => 120,
+            Self::Baumwolle => 130,
+            Self::Leder => 200,
+            Self::Samt => 400,
+            Self::Pelz => 500,
+            Self::Seide => 600,
+        }
+    }
+
+    /// what people of this stratum can afford
+    fn affordable(self, stratum: Stratum) -> bool {
+        use Material::*;
+        match stratum {
+            Stratum::Rich => !matches!(self, Sackleinen | Leinen),
+            Stratum::Normal => matches!(self, Leinen | Wolle | Baumwolle | Leder),
+            Stratum::Poor => matches!(self, Sackleinen | Leinen | Wolle),
+            Stratum::Beggar => self == Sackleinen,
+        }
+    }
+}
+
+impl Display for Material {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Condition {
+    New,
+    Good,
+    Worn,
+    Ragged,
+}
+
+impl Condition {
+    pub const ALL: [Condition; 4] = [Self::New, Self::Good, Self::Worn, Self::Ragged];
+
+    /// value in percent of a new piece
+    pub fn percent(self) -> u64 {
+        match self {
+            Self::New => 100,
+            Self::Good => 75,
+            Self::Worn => 40,
+            Self::Ragged => 10,
+        }
+    }
+
+    /// weights for [`Condition::ALL`]
+    fn weights(stratum: Stratum) -> [u32; 4] {
+        match stratum {
+            Stratum::Rich => [60, 35, 5, 0],
+            Stratum::Normal => [15, 55, 25, 5],
+            Stratum::Poor => [0, 20, 60, 20],
+            Stratum::Beggar => [0, 0, 30, 70],
+        }
+    }
+}
+
+impl Display for Condition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::New => "neu",
+            Self::Good => "gut erhalten",
+            Self::Worn => "abgetragen",
+            Self::Ragged => "zerlumpt",
+        };
+        write!(f, "{s}")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Slot {
+    Kopf,
+    Oberkoerper,
+    Beine,
+    Fuesse,
+    Guertel,
+    Umhang,
+}
+
+struct GarmentKind {
+    name: &'static str,
+    slot: Slot,
+    /// in Kreuzer, new and made of linen
+    kreuzer: u32,
+    materials: &'static [Material],
+    /// None is worn everywhere
+    regions: Option<&'static [Region]>,
+}
+
+const fn kind(
+    name: &'static str,
+    slot: Slot,
+    kreuzer: u32,
+    materials: &'static [Material],
+) -> GarmentKind {
+    GarmentKind {
+        name,
+        slot,
+        kreuzer,
+        materials,
+        regions: None,
+    }
+}
+
+const fn regional(
+    name: &'static str,
+    slot: Slot,
+    kreuzer: u32,
+    materials: &'static [Material],
+    regions: &'static [Region],
+) -> GarmentKind {
+    GarmentKind {
+        name,
+        slot,
+        kreuzer,
+        materials,
+        regions: Some(regions),
+    }
+}
+
+const SUEDEN: &[Region] = &[Region::Tulamidenlande, Region::AlAnfa];
+const NORDEN: &[Region] = &[Region::Nostria, Region::Thorwal, Region::Bornland];
+
+const GARMENTS: &[GarmentKind] = {
+    use Material::*;
+    use Slot::*;
+    &[
+        kind(
+            "Hemd",
+            Oberkoerper,
+            60,
+            &[Sackleinen, Leinen, Baumwolle, Seide],
+        ),
+        kind("Kittel", Oberkoerper, 50, &[Sackleinen, Leinen, Wolle]),
+        kind(
+            "Tunika",
+            Oberkoerper,
+            80,
+            &[Sackleinen, Leinen, Wolle, Seide],
+        ),
+        kind("Wams", Oberkoerper, 300, &[Wolle, Leder, Samt]),
+        kind("Bluse", Oberkoerper, 100, &[Leinen, Baumwolle, Seide]),
+        regional(
+            "Kaftan",
+            Oberkoerper,
+            400,
+            &[Leinen, Baumwolle, Seide],
+            SUEDEN,
+        ),
+        kind("Hose", Beine, 80, &[Sackleinen, Leinen, Wolle, Leder]),
+        kind("Rock", Beine, 80, &[Sackleinen, Leinen, Wolle, Samt, Seide]),
+        regional("Pluderhose", Beine, 150, &[Baumwolle, Seide], SUEDEN),
+        kind("Stiefel", Fuesse, 500, &[Leder]),
+        kind("Schuhe", Fuesse, 200, &[Leder, Samt]),
+        kind("Fußlappen", Fuesse, 5, &[Sackleinen, Leinen]),
+        regional(
+            "Sandalen",
+            Fuesse,
+            50,
+            &[Leder],
+            &[Region::Tulamidenlande, Region::AlAnfa, Region::Horasreich],
+        ),
+        regional("Fellstiefel", Fuesse, 400, &[Leder, Pelz], NORDEN),
+        kind("Hut", Kopf, 150, &[Wolle, Leder, Samt]),
+        kind("Mütze", Kopf, 40, &[Leinen, Wolle, Pelz]),
+        kind("Kapuze", Kopf, 30, &[Sackleinen, Leinen, Wolle]),
+        regional(
+            "Turban",
+            Kopf,
+            120,
+            &[Baumwolle, Seide],
+            &[Region::Tulamidenlande],
+        ),
+        regional("Barett", Kopf, 300, &[Samt], &[Region::Horasreich]),
+        regional(
+            "Pelzkappe",
+            Kopf,
+            200,
+            &[Pelz],
+            &[Region::Thorwal, Region::Bornland],
+        ),
+        kind("Gürtel", Guertel, 50, &[Leder]),
+        kind("Kordel", Guertel, 5, &[Sackleinen, Leinen]),
+        regional("Schärpe", Guertel, 80, &[Baumwolle, Seide], SUEDEN),
+        kind("Umhang", Umhang, 200, &[Sackleinen, Leinen, Wolle, Samt]),
+        kind("Mantel", Umhang, 600, &[Wolle, Leder, Samt, Pelz]),
+    ]
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Garment {
+    pub name: &'static str,
+    pub slot: Slot,
+    pub material: Material,
+    pub condition: Condition,
+    /// what a second-hand dealer would pay, roughly
+    pub value: Money,
+}
+
+impl Display for Garment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} aus {} ({})",
+            self.name, self.material, self.condition
+        )
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Outfit {
+    pub garments: Vec<Garment>,
+}
+
+impl Outfit {
+    pub fn random(rng: &mut impl Rng, stratum: Stratum, region: Region, season: Season) -> Self {
+        let climate = region.climate();
+        let mut slots = vec![Slot::Oberkoerper, Slot::Beine];
+        // beggars often go barefoot
+        if stratum != Stratum::Beggar || rng.random_bool(0.5) {
+            slots.push(Slot::Fuesse);
+        }
+        if rng.random_bool(if season == Season::Winter { 0.9 } else { 0.5 }) {
+            slots.push(Slot::Kopf);
+        }
+        if rng.random_bool(0.6) {
+            slots.push(Slot::Guertel);
+        }
+        if rng.random_bool(season.cloak_chance(climate)) {
+            slots.push(Slot::Umhang);
+        }
+
+        let fur = season.fur(climate);
+        let wearable = |m: &&Material| m.affordable(stratum) && (fur || **m != Material::Pelz);
+        let garments = slots
+            .into_iter()
+            .filter_map(|slot| {
+                let kinds: Vec<&GarmentKind> = GARMENTS
+                    .iter()
+                    .filter(|k| k.slot == slot)
+                    .filter(|k| k.regions.is_none_or(|r| r.contains(&region)))
+                    .filter(|k| k.materials.iter().any(|m| wearable(&m)))
+                    .collect();
+                // the local fashion wins over what is worn everywhere
+                let kind = kinds
+                    .choose_weighted(rng, |k| if k.regions.is_some() { 4 } else { 1 })
+                    .ok()?;
+                let materials: Vec<&Material> = kind.materials.iter().filter(wearable).collect();
+                let material = **materials.choose(rng)?;
+                let condition = *Condition::ALL
+                    .iter()
+                    .zip(Condition::weights(stratum))
+                    .collect::<Vec<_>>()
+                    .choose_weighted(rng, |(_, w)| *w)
+                    .ok()?
+                    .0;
+                Some(Garment {
+                    name: kind.name,
+                    slot,
+                    material,
+                    condition,
+                    value: Money::from_kreuzer(kind.kreuzer)
+                        .percent(material.percent())
+                        .percent(condition.percent()),
+                })
+            })
+            .collect();
+        Self { garments }
+    }
+
+    pub fn value(&self) -> Money {
+        self.garments.iter().map(|g| g.value).sum()
+    }
+}
+
+impl Display for Outfit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let garments: Vec<String> = self.garments.iter().map(Garment::to_string).collect();
+        write!(f, "{}, Wert etwa {}", garments.join(", "), self.value())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::{SeedableRng, rngs::StdRng};
+
+    use super::{Climate, Material, Outfit, Region, Season, Slot};
+    use crate::Stratum;
+
+    #[test]
+    fn outfits_fit_stratum_and_season() {
+        let mut rng = StdRng::seed_from_u64(9);
+        for stratum in Stratum::ALL {
+            for season in Season::ALL {
+                for region in Region::ALL {
+                    let outfit = Outfit::random(&mut rng, stratum, region, season);
+                    let has = |slot| outfit.garments.iter().any(|g| g.slot == slot);
+                    assert!(has(Slot::Oberkoerper) && has(Slot::Beine), "{outfit}");
+                    if season == Season::Winter && region.climate() != Climate::Hot {
+                        assert!(has(Slot::Umhang), "{outfit}");
+                    }
+                    for g in &outfit.garments {
+                        assert!(g.material.affordable(stratum), "{g}");
+                        assert!(season.fur(region.climate()) || g.material != Material::Pelz);
+                    }
+                }
+            }
+        }
+        let reich = Outfit::random(&mut rng, Stratum::Rich, Region::Mittelreich, Season::Summer);
+        let bettler = Outfit::random(
+            &mut rng,
+            Stratum::Beggar,
+            Region::Mittelreich,
+            Season::Summer,
+        );
+        assert!(reich.value() > bettler.value());
+
+        // Thorwal and the Mittelreich pay in Dukaten but dress differently
+        let names = |region| {
+            let mut rng = StdRng::seed_from_u64(3);
+            (0..200)
+                .flat_map(|_| {
+                    Outfit::random(&mut rng, Stratum::Normal, region, Season::Autumn).garments
+                })
+                .map(|g| g.name)
+                .collect::<Vec<_>>()
+        };
+        assert!(names(Region::Thorwal).contains(&"Fellstiefel"));
+        assert!(!names(Region::Mittelreich).contains(&"Fellstiefel"));
+        assert!(!names(Region::Thorwal).contains(&"Kaftan"));
+    }
+}
@@ -0,0 +1,219 @@
+//! Regional coinage. All values are measured in Mittelreich Kreuzer, so [`Money`] stays the
+//! common unit and a currency only decides which coins it is paid in.
+
+use std::fmt::Display;
+
+use crate::money::{Coins, Money};
+
+/// no currency has more coins than this
+pub const MAX_DENOMINATIONS: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Denomination {
+    pub name: &'static str,
+    pub plural: &'static str,
+    /// worth in Mittelreich Kreuzer
+    pub kreuzer: u64,
+}
+
+const fn coin(name: &'static str, plural: &'static str, kreuzer: u64) -> Denomination {
+    Denomination {
+        name,
+        plural,
+        kreuzer,
+    }
+}
+
+const DUKAT: Denomination = coin("Dukat", "Dukaten", 1000);
+const SILBERTALER: Denomination = coin("Silbertaler", "Silbertaler", 100);
+const HELLER: Denomination = coin("Heller", "Heller", 10);
+const KREUZER: Denomination = coin("Kreuzer", "Kreuzer", 1);
+
+const MITTELREICH: &[Denomination] = &[DUKAT, SILBERTALER, HELLER, KREUZER];
+const HORASREICH: &[Denomination] = &[
+    coin("Horasdor", "Horasdor", 20000),
+    DUKAT,
+    coin("Kusliker", "Kusliker", 100),
+    HELLER,
+    KREUZER,
+];
+const BORNLAND: &[Denomination] = &[
+    DUKAT,
+    coin("Batzen", "Batzen", 100),
+    coin("Groschen", "Groschen", 10),
+    coin("Deut", "Deut", 1),
+];
+const KALIFAT: &[Denomination] = &[
+    coin("Marawedi", "Marawedi", 2000),
+    coin("Zechine", "Zechinen", 100),
+    coin("Muwlat", "Muwlat", 1),
+];
+const AL_ANFA: &[Denomination] = &[
+    coin("Dublone", "Dublonen", 2000),
+    coin("Oreal", "Oreal", 100),
+    coin("Kleiner Oreal", "Kleine Oreal", 50),
+    coin("Dirham", "Dirham", 1),
+];
+const ZWERGENREICHE: &[Denomination] = &[
+    coin("Zwergentaler", "Zwergentaler", 10000),
+    DUKAT,
+    SILBERTALER,
+    HELLER,
+    KREUZER,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Currency {
+    #[default]
+    Mittelreich,
+    Horasreich,
+    Bornland,
+    Kalifat,
+    AlAnfa,
+    Zwergenreiche,
+}
+
+impl Currency {
+    pub const ALL: [Currency; 6] = [
+        Self::Mittelreich,
+        Self::Horasreich,
+        Self::Bornland,
+        Self::Kalifat,
+        Self::AlAnfa,
+        Self::Zwergenreiche,
+    ];
+
+    /// from the most valuable coin down, the last one is always worth one Kreuzer
+    pub fn denominations(self) -> &'static [Denomination] {
+        match self {
+            Self::Mittelreich => MITTELREICH,
+            Self::Horasreich => HORASREICH,
+            Self::Bornland => BORNLAND,
+            Self::Kalifat => KALIFAT,
+            Self::AlAnfa => AL_ANFA,
+            Self::Zwergenreiche => ZWERGENREICHE,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Mittelreich => "Mittelreich",
+            Self::Horasreich => "Horasreich",
+            Self::Bornland => "Bornland",
+            Self::Kalifat => "Kalifat",
+            Self::AlAnfa => "Al'Anfa",
+            Self::Zwergenreiche => "Zwergenreiche",
+        }
+    }
+
+    /// Changers take less for the neighbours' coins than for those from far away.
+    /// Coins of the same realm are changed for free.
+    pub fn exchange_fee_percent(self, to: Currency) -> u64 {
+        use Currency::*;
+        match (self, to) {
+            (a, b) if a == b => 0,
+            (Mittelreich, Horasreich | Bornland | Zwergenreiche)
+            | (Horasreich | Bornland | Zwergenreiche, Mittelreich)
+            | (Kalifat, AlAnfa)
+            | (AlAnfa, Kalifat) => 5,
+            _ => 10,
+        }
+    }
+
+    /// how many of `to`'s largest coin one gets for the largest coin of `self`, before fees
+    pub fn exchange_rate(self, to: Currency) -> f64 {
+        self.denominations()[0].kreuzer as f64 / to.denominations()[0].kreuzer as f64
+    }
+}
+
+impl Display for Currency {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// the result of a visit to the money changer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Exchange {
+    pub received: Coins,
+    pub fee: Money,
+}
+
+/// changes `coins` into the fewest coins of `to`, minus the changer's fee.
+/// Fractions of a Kreuzer go to the changer.
+pub fn exchange(coins: Coins, to: Currency) -> Exchange {
+    let amount = Money::from(coins);
+    let received = amount.percent(100 - coins.currency().exchange_fee_percent(to));
+    Exchange {
+        received: Coins::exact(received, to),
+        fee: amount - received,
+    }
+}
+
+/// the exchange rates between the largest coins of all currencies
+pub fn exchange_table() -> String {
+    let mut table = String::new();
+    for from in Currency::ALL {
+        for to in Currency::ALL {
+            if from != to {
+                table += &format!(
+                    "1 {} ({from}) = {:.2} {} ({to}), {}% Gebühr\n",
+                    from.denominations()[0].name,
+                    from.exchange_rate(to),
+                    to.denominations()[0].plural,
+                    from.exchange_fee_percent(to),
+                );
+            }
+        }
+    }
+    table
+}
+
+#[cfg(test)]
+mod test {
+    use rand::{Rng, SeedableRng, rngs::StdRng};
+
+    use super::{Currency, MAX_DENOMINATIONS, exchange};
+    use crate::money::{Coins, Money};
+
+    #[test]
+    fn currencies_round_trip() {
+        let mut rng = StdRng::seed_from_u64(4);
+        for currency in Currency::ALL {
+            let denominations = currency.denominations();
+            assert!(denominations.len() <= MAX_DENOMINATIONS);
+            assert_eq!(denominations.last().unwrap().kreuzer, 1, "{currency}");
+            assert!(
+                denominations
+                    .windows(2)
+                    .all(|w| w[0].kreuzer > w[1].kreuzer)
+            );
+            for _ in 0..200 {
+                let amount = Money::from_kreuzer(rng.random_range(0..50_000));
+                let coins = Coins::new_random_in(amount, currency, &mut rng);
+                assert_eq!(coins.currency(), currency);
+                assert_eq!(Money::from(coins), amount);
+                // what we print can be read back, even two word coins and an empty purse
+                assert_eq!(coins.to_string().parse::<Money>(), Ok(amount), "{coins}");
+            }
+        }
+    }
+
+    #[test]
+    fn changing_costs_a_fee() {
+        let dukaten = Coins::exact(Money::from_dukaten(10), Currency::Mittelreich);
+        let change = exchange(dukaten, Currency::AlAnfa);
+        assert_eq!(change.fee, Money::from_dukaten(1));
+        assert_eq!(change.received.to_string(), "4 Dublonen, 10 Oreal");
+        assert_eq!(exchange(dukaten, Currency::Mittelreich).fee, Money::ZERO);
+        assert_eq!(
+            "2 Dublonen".parse::<Money>().unwrap(),
+            Money::from_dukaten(4)
+        );
+        assert_eq!(
+            "3 Kleine Oreal und 1 Kleiner Oreal, 2 Dirham".parse::<Money>(),
+            Ok(Money::from_kreuzer(202))
+        );
+        assert!("3 Kleine".parse::<Money>().is_err());
+    }
+}
@@ -0,0 +1,317 @@
+//! What a fence pays for stolen goods. The more recognisable a piece is, the hotter it is,
+//! and the less anyone wants to touch it.
+
+use std::fmt::Display;
+
+use generate::dsa5::Probe;
+use rand::{SeedableRng, rngs::StdRng};
+
+use crate::{
+    currency::Currency,
+    items::{LootItem, PersonLootItems, Rarity},
+    market::{Haggler, Haggling, PERCENT_PER_QS, SettlementSize, haggle},
+    money::Money,
+    provenance::Provenance,
+};
+
+/// every level of heat costs this many percent of the list price
+const PERCENT_PER_HEAT: u64 = 10;
+/// the fence never pays less than this for something he takes
+const MIN_PERCENT: u64 = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Greed {
+    /// an old friend
+    Fair,
+    #[default]
+    Normal,
+    Greedy,
+}
+
+impl Greed {
+    pub const ALL: [Greed; 3] = [Self::Fair, Self::Normal, Self::Greedy];
+
+    /// share of the list price for a piece nobody is looking for
+    fn percent(self) -> u64 {
+        match self {
+            Self::Fair => 60,
+            Self::Normal => 50,
+            Self::Greedy => 35,
+        }
+    }
+}
+
+impl Display for Greed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::Fair => "fair",
+            Self::Normal => "gewöhnlich",
+            Self::Greedy => "gierig",
+        };
+        write!(f, "{s}")
+    }
+}
+
+/// how dangerous a piece is to deal in
+pub fn heat(item: &LootItem) -> u8 {
+    let provenance = match item.flavour.provenance {
+        None => 0,
+        Some(Provenance::Handelshaus { .. }) => 1,
+        Some(Provenance::Adel { .. }) => 2,
+        Some(Provenance::Tempel { .. }) => 3,
+    };
+    // magical and rare things are remembered
+    let unique = !item.properties.is_empty() as u8 + (item.record.rarity == Rarity::Rare) as u8;
+    provenance + item.flavour.marks.len() as u8 + unique
+}
+
+/// hottest piece a fence still takes, in a big city things vanish more easily
+fn max_heat(settlement: SettlementSize) -> u8 {
+    match settlement {
+        SettlementSize::Village => 1,
+        SettlementSize::Town => 2,
+        SettlementSize::City => 4,
+        SettlementSize::Metropolis => 6,
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FenceConfig {
+    pub settlement: SettlementSize,
+    pub currency: Currency,
+    pub greed: Greed,
+    /// None takes the first offer
+    pub haggler: Option<Haggler>,
+}
+
+#[derive(Debug, Clone)]
+pub struct FenceItemOffer {
+    pub item: LootItem,
+    pub heat: u8,
+    /// None if the fence refuses or the item is worthless
+    pub offer: Option<Money>,
+}
+
+#[derive(Debug, Clone)]
+pub struct FenceOffer {
+    pub config: FenceConfig,
+    pub items: Vec<FenceItemOffer>,
+    /// the fence's first offer for everything he takes
+    pub offer: Money,
+    /// worst and best outcome of haggling
+    pub range: (Money, Money),
+    /// the party is the `buyer` of the check, its QS raise the price
+    pub haggling: Option<Haggling>,
+    /// what is paid out in the end
+    pub payout: Money,
+}
+
+pub struct Fence {
+    rng: StdRng,
+    config: FenceConfig,
+}
+
+impl Fence {
+    pub fn new(config: FenceConfig) -> Self {
+        Self {
+            rng: StdRng::from_os_rng(),
+            config,
+        }
+    }
+
+    /// not an [`RPGenerator`](generate::RPGenerator) since it needs the loot, but just as reproducible
+    pub fn seed(&mut self, s: u64) {
+        self.rng = StdRng::seed_from_u64(s);
+    }
+
+    pub fn offer(&mut self, loot: &PersonLootItems) -> FenceOffer {
+        let FenceConfig {
+            settlement,
+            greed,
+            haggler,
+            ..
+        } = self.config;
+        let items: Vec<FenceItemOffer> = loot
+            .0
+            .iter()
+            .map(|item| {
+                let heat = heat(item);
+                let percent = greed
+                    .percent()
+                    .saturating_sub(heat as u64 * PERCENT_PER_HEAT)
+                    .max(MIN_PERCENT);
+                FenceItemOffer {
+                    item: item.clone(),
+                    heat,
+                    offer: item
+                        .value()
+                        .filter(|_| heat <= max_heat(settlement))
+                        .map(|v| v.percent(percent))
+                        .filter(|v| *v > Money::ZERO),
+                }
+            })
+            .collect();
+        let offer: Money = items.iter().filter_map(|i| i.offer).sum();
+        let max_percent = (6 * PERCENT_PER_QS) as u64;
+        let haggling = haggler.map(|h| haggle(&mut self.rng, settlement, h));
+        // haggling turned around: what is cheaper for a buyer is more for the seller
+        let percent = 100 - haggling.map_or(0, |h| h.percent);
+        FenceOffer {
+            config: self.config,
+            items,
+            offer,
+            range: (
+                offer.percent(100 - max_percent),
+                offer.percent(100 + max_percent),
+            ),
+            haggling,
+            payout: offer.percent(percent as u64),
+        }
+    }
+}
+
+impl Display for FenceOffer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let currency = self.config.currency;
+        writeln!(
+            f,
+            "Hehler, {} ({}), {}",
+            self.config.settlement, currency, self.config.greed
+        )?;
+        for item in &self.items {
+            match item.offer {
+                Some(offer) => writeln!(
+                    f,
+                    "{}: {} (Hitze {})",
+                    item.item,
+                    offer.in_currency(currency),
+                    item.heat
+                )?,
+                None => writeln!(f, "{}: will er nicht (Hitze {})", item.item, item.heat)?,
+            }
+        }
+        writeln!(
+            f,
+            "Angebot: {}, beim Feilschen {} bis {}",
+            self.offer.in_currency(currency),
+            self.range.0.in_currency(currency),
+            self.range.1.in_currency(currency)
+        )?;
+        if let Some(h) = &self.haggling {
+            let qs = |p: &Probe| {
+                p.qs.map_or("misslungen".to_string(), |qs| format!("QS {qs}"))
+            };
+            writeln!(
+                f,
+                "Feilschen: {} gegen {}, Angebot {:+}%",
+                qs(&h.buyer),
+                qs(&h.merchant),
+                -h.percent
+            )?;
+        }
+        write!(f, "Auszahlung: {}", self.payout.in_currency(currency))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use generate::RPGenerator;
+
+    use super::{Fence, FenceConfig, Greed, heat};
+    use crate::{
+        PersonConfig, Schatzi, Stratum,
+        currency::Currency,
+        items::{LootItem, LootStash, PersonLootItems, Rarity},
+        market::{Haggler, SettlementSize},
+        provenance::{Flavour, Provenance},
+    };
+
+    #[test]
+    fn heat_and_settlement() {
+        let record = LootStash::builtin()
+            .items()
+            .iter()
+            .find(|r| r.value.is_some() && r.rarity == Rarity::Common)
+            .unwrap()
+            .clone();
+        let plain = LootItem {
+            record,
+            amount: 1,
+            properties: vec![],
+            flavour: Flavour::default(),
+        };
+        let mut hot = plain.clone();
+        hot.flavour.provenance = Some(Provenance::Adel { haus: "Rabenmund" });
+        hot.flavour.marks = vec!["eine Gravur"];
+        assert_eq!((heat(&plain), heat(&hot)), (0, 3));
+
+        let offer = |settlement, item: &LootItem| {
+            Fence::new(FenceConfig {
+                settlement,
+                ..Default::default()
+            })
+            .offer(&PersonLootItems(vec![item.clone()]))
+            .items[0]
+                .offer
+        };
+        let value = plain.value().unwrap();
+        // half the price for a clean piece, three levels of heat take another 30%
+        assert_eq!(offer(SettlementSize::City, &plain), Some(value.percent(50)));
+        assert_eq!(offer(SettlementSize::City, &hot), Some(value.percent(20)));
+        // a village fence does not touch the crest, in the metropolis it vanishes
+        assert!(offer(SettlementSize::Village, &plain).is_some());
+        assert_eq!(offer(SettlementSize::Village, &hot), None);
+        assert!(offer(SettlementSize::Metropolis, &hot).is_some());
+
+        let offer = Fence::new(FenceConfig {
+            currency: Currency::Kalifat,
+            ..Default::default()
+        })
+        .offer(&PersonLootItems(vec![plain]));
+        let text = offer.to_string();
+        let angebot = text.lines().find(|l| l.starts_with("Angebot")).unwrap();
+        assert!(
+            angebot.contains(&offer.offer.in_currency(Currency::Kalifat).to_string()),
+            "{text}"
+        );
+    }
+
+    #[test]
+    fn hot_goods_fetch_less() {
+        let mut schatzi = Schatzi::new(PersonConfig {
+            stratum: Some(Stratum::Rich),
+            ..Default::default()
+        });
+        schatzi.seed(43);
+        let loot: Vec<_> = schatzi.take(100).map(|l| l.items).collect();
+        let mut totals = vec![];
+        for greed in Greed::ALL {
+            let mut fence = Fence::new(FenceConfig {
+                settlement: SettlementSize::City,
+                greed,
+                haggler: Some(Haggler {
+                    eigenschaften: [13, 13, 13],
+                    fw: 8,
+                }),
+                ..Default::default()
+            });
+            fence.seed(1);
+            let mut total = 0;
+            for items in &loot {
+                let offer = fence.offer(items);
+                assert!(offer.range.0 <= offer.payout && offer.payout <= offer.range.1);
+                for item in &offer.items {
+                    if heat(&item.item) > 4 {
+                        assert_eq!(item.offer, None);
+                    }
+                    if let (Some(offer), Some(value)) = (item.offer, item.item.value()) {
+                        assert!(offer < value, "{}", item.item);
+                    }
+                }
+                total += offer.offer.as_kreuzer();
+            }
+            totals.push(total);
+        }
+        assert!(totals.windows(2).all(|w| w[0] > w[1]), "{totals:?}");
+    }
+}
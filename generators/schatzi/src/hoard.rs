@@ -0,0 +1,403 @@
+//! Hoards for dungeons and monster lairs, as opposed to what a single person carries.
+
+use std::fmt::Display;
+
+use generate::RPGenerator;
+use rand::{Rng, SeedableRng, rngs::StdRng, seq::IndexedRandom};
+
+use crate::{
+    currency::Currency,
+    items::{LootItemRecord, LootStash},
+    money::{Coins, Money},
+    weight::{Gram, Weight},
+};
+
+/// name, value in Kreuzer at level 1, weight
+const KUNSTGEGENSTAENDE: &[(&str, u32, Gram)] = &[
+    ("Silberner Kelch mit Gravur", 3000, 400),
+    ("Goldene Statuette eines Greifen", 12000, 2000),
+    ("Gobelin mit Jagdszene", 6000, 5000),
+    ("Elfenbeinschnitzerei", 8000, 500),
+    ("Bemalte Porzellanvase", 4000, 1500),
+    ("Vergoldeter Spiegel", 5000, 3000),
+    ("Ölgemälde eines Edelmanns", 7000, 4000),
+    ("Kristallkaraffe", 2500, 1000),
+    ("Perlenbestickter Mantel", 9000, 2500),
+];
+
+/// art a temple keeps
+const SAKRALE_KUNST: &[(&str, u32, Gram)] = &[
+    ("Goldenes Weihrauchfass", 10000, 1500),
+    ("Silberne Opferschale", 4000, 800),
+    ("Reliquienschrein", 15000, 8000),
+    ("Bestickte Altardecke", 3000, 1500),
+    ("Ikone mit Blattgold", 6000, 2000),
+];
+
+/// name, value in Kreuzer, weight
+const ARTEFAKTE: &[(&str, u32, Gram)] = &[
+    ("Ring mit Zauberzeichen", 50000, 10),
+    ("Amulett gegen Untote", 30000, 40),
+    ("Unzerbrechlicher Dolch", 40000, 500),
+    ("Stab mit eingelegtem Bergkristall", 60000, 1500),
+    ("Immerwarmer Umhang", 25000, 1500),
+    ("Schutzrune auf Bronzescheibe", 20000, 200),
+];
+
+/// gems are appraised by their quality
+const GEM_QUALITIES: &[(&str, u64)] = &[
+    ("trüb", 50),
+    ("gewöhnlich", 100),
+    ("klar", 150),
+    ("lupenrein", 250),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Theme {
+    Drachenhort,
+    #[default]
+    Raeuberlager,
+    Tempelschatz,
+}
+
+impl Theme {
+    pub const ALL: [Theme; 3] = [Self::Drachenhort, Self::Raeuberlager, Self::Tempelschatz];
+
+    /// coins at level 1 in Kreuzer, they grow with the square of the level
+    fn coins(self) -> (u32, u32) {
+        match self {
+            Self::Drachenhort => (5000, 20000),
+            Self::Raeuberlager => (500, 3000),
+            Self::Tempelschatz => (1000, 6000),
+        }
+    }
+
+    /// how many gems, art objects, magic items and other items at most, before the level bonus
+    fn counts(self) -> [u32; 4] {
+        match self {
+            Self::Drachenhort => [6, 3, 2, 2],
+            Self::Raeuberlager => [1, 1, 0, 6],
+            Self::Tempelschatz => [2, 3, 1, 3],
+        }
+    }
+
+    /// which items from the stash fit the theme, None means anything
+    fn item_tags(self) -> Option<&'static [&'static str]> {
+        match self {
+            Self::Drachenhort => Some(&["schmuck"]),
+            Self::Raeuberlager => None,
+            Self::Tempelschatz => Some(&["religioes"]),
+        }
+    }
+}
+
+impl Display for Theme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::Drachenhort => "Drachenhort",
+            Self::Raeuberlager => "Räuberlager",
+            Self::Tempelschatz => "Tempelschatz",
+        };
+        write!(f, "{s}")
+    }
+}
+
+/// anything in a hoard that is not coins, with what a fence or appraiser would put on it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Valuable {
+    pub name: String,
+    pub value: Money,
+    pub weight: Weight,
+}
+
+#[derive(Debug, Clone)]
+pub struct Hoard {
+    pub theme: Theme,
+    pub level: u8,
+    pub coins: Coins,
+    pub gems: Vec<Valuable>,
+    pub art: Vec<Valuable>,
+    pub magic: Vec<Valuable>,
+    pub items: Vec<(LootItemRecord, u32)>,
+}
+
+impl Hoard {
+    /// everything together, items without a known value count as nothing
+    pub fn value(&self) -> Money {
+        let valuables: Money = self
+            .gems
+            .iter()
+            .chain(&self.art)
+            .chain(&self.magic)
+            .map(|v| v.value)
+            .sum();
+        let items: Money = self
+            .items
+            .iter()
+            .filter_map(|(item, n)| Some(item.value? * *n))
+            .sum();
+        Money::from(self.coins) + valuables + items
+    }
+
+    /// what has to be carried out of the dungeon
+    pub fn weight(&self) -> Weight {
+        let valuables: Weight = self
+            .gems
+            .iter()
+            .chain(&self.art)
+            .chain(&self.magic)
+            .map(|v| v.weight)
+            .sum();
+        let items: Weight = self.items.iter().map(|(item, n)| item.weight * *n).sum();
+        self.coins.weight() + valuables + items
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct HoardConfig {
+    /// challenge level, 1 is a handful of goblins
+    pub level: u8,
+    /// None picks one at random
+    pub theme: Option<Theme>,
+    pub currency: Currency,
+}
+
+impl Default for HoardConfig {
+    fn default() -> Self {
+        Self {
+            level: 1,
+            theme: None,
+            currency: Currency::default(),
+        }
+    }
+}
+
+pub struct HoardGenerator {
+    rng: StdRng,
+    items: Vec<LootItemRecord>,
+    config: HoardConfig,
+}
+
+impl HoardGenerator {
+    pub fn new(config: HoardConfig) -> Self {
+        Self::with_stash(&LootStash::builtin(), config)
+    }
+    pub fn with_stash(stash: &LootStash, config: HoardConfig) -> Self {
+        Self {
+            rng: StdRng::from_os_rng(),
+            items: stash.items().to_vec(),
+            config,
+        }
+    }
+
+    pub fn set_config(&mut self, config: HoardConfig) {
+        self.config = config;
+    }
+
+    fn tagged(&self, tag: &str) -> Vec<&LootItemRecord> {
+        self.items
+            .iter()
+            .filter(|item| item.tags.iter().any(|t| t == tag))
+            .collect()
+    }
+
+    /// up to `max` picks from `table`, values scaled by the level
+    fn valuables(&mut self, table: &[(&str, u32, Gram)], max: u32, level: u32) -> Vec<Valuable> {
+        let n = self.rng.random_range(0..=max);
+        (0..n)
+            .filter_map(|_| table.choose(&mut self.rng))
+            .map(|&(name, value, weight)| Valuable {
+                name: name.to_string(),
+                value: Money::from_kreuzer(value) * level,
+                weight: Weight::from_gram(weight),
+            })
+            .collect()
+    }
+
+    fn gems(&mut self, max: u32) -> Vec<Valuable> {
+        let gems: Vec<LootItemRecord> = self.tagged("edelstein").into_iter().cloned().collect();
+        let n = self.rng.random_range(0..=max);
+        (0..n)
+            .filter_map(|_| {
+                let gem = gems.choose(&mut self.rng)?;
+                let (quality, percent) = GEM_QUALITIES.choose(&mut self.rng)?;
+                Some(Valuable {
+                    name: format!("{} ({quality})", gem.name),
+                    value: gem.value.unwrap_or(Money::ZERO).percent(*percent),
+                    weight: gem.weight,
+                })
+            })
+            .collect()
+    }
+}
+
+impl Iterator for HoardGenerator {
+    type Item = Hoard;
+    fn next(&mut self) -> Option<Self::Item> {
+        let HoardConfig {
+            level,
+            theme,
+            currency,
+        } = self.config;
+        let theme = match theme {
+            Some(t) => t,
+            None => *Theme::ALL.choose(&mut self.rng)?,
+        };
+        let level = level.max(1) as u32;
+        let (min, max) = theme.coins();
+        let coins = Money::from_kreuzer(self.rng.random_range(min..=max)) * (level * level);
+        let [gems, art, magic, items] = theme.counts().map(|n| n + level / 2);
+
+        let art_table = match theme {
+            Theme::Tempelschatz => SAKRALE_KUNST,
+            _ => KUNSTGEGENSTAENDE,
+        };
+        // the lowest levels seldom have anything magical
+        let magic_count = if theme.counts()[2] == 0 && level < 3 {
+            0
+        } else {
+            magic
+        };
+        let mut magic = self.valuables(ARTEFAKTE, magic_count.min(level), 1);
+        // potions are magical too
+        let potions: Vec<LootItemRecord> = self.tagged("trank").into_iter().cloned().collect();
+        if let Some(potion) = potions.choose(&mut self.rng)
+            && self.rng.random_bool(0.5)
+        {
+            magic.push(Valuable {
+                name: potion.name.clone(),
+                value: potion.value.unwrap_or(Money::ZERO),
+                weight: potion.weight,
+            });
+        }
+
+        let candidates: Vec<LootItemRecord> = match theme.item_tags() {
+            Some(tags) => self
+                .items
+                .iter()
+                .filter(|item| item.tags.iter().any(|t| tags.contains(&t.as_str())))
+                .cloned()
+                .collect(),
+            None => self.items.clone(),
+        };
+        let n = self.rng.random_range(0..=items) as usize;
+        let items = candidates
+            .choose_multiple(&mut self.rng, n)
+            .map(|item| {
+                (
+                    item.clone(),
+                    self.rng.random_range(1..=item.max_pp.max(1) as u32),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        Some(Hoard {
+            theme,
+            level: level as u8,
+            coins: Coins::new_random_in(coins, currency, &mut self.rng),
+            gems: self.gems(gems),
+            art: self.valuables(art_table, art, level),
+            magic,
+            items,
+        })
+    }
+}
+
+impl RPGenerator for HoardGenerator {
+    type Seed = u64;
+    fn seed(&mut self, s: Self::Seed) {
+        self.rng = StdRng::seed_from_u64(s);
+    }
+}
+
+impl Display for Hoard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{} (Stufe {}), Gesamtwert {}, Gewicht {}",
+            self.theme,
+            self.level,
+            self.value(),
+            self.weight()
+        )?;
+        writeln!(f, "Münzen: {}", self.coins)?;
+        for (title, valuables) in [
+            ("Edelsteine", &self.gems),
+            ("Kunstgegenstände", &self.art),
+            ("Magisches", &self.magic),
+        ] {
+            if !valuables.is_empty() {
+                let list: Vec<String> = valuables
+                    .iter()
+                    .map(|v| format!("{} ({})", v.name, v.value))
+                    .collect();
+                writeln!(f, "{title}: {}", list.join(", "))?;
+            }
+        }
+        if !self.items.is_empty() {
+            let list: Vec<String> = self
+                .items
+                .iter()
+                .map(|(item, n)| {
+                    if *n == 1 {
+                        item.name.clone()
+                    } else {
+                        format!("{n}x {}", item.name)
+                    }
+                })
+                .collect();
+            writeln!(f, "Sonstiges: {}", list.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use generate::RPGenerator;
+
+    use super::{HoardConfig, HoardGenerator, Theme};
+    use crate::weight::Weight;
+
+    #[test]
+    fn hoards_grow_with_level() {
+        for theme in Theme::ALL {
+            let mut values = vec![];
+            let mut weights = vec![];
+            for level in [1, 5, 10] {
+                let mut generator = HoardGenerator::new(HoardConfig {
+                    level,
+                    theme: Some(theme),
+                    ..Default::default()
+                });
+                generator.seed(level as u64);
+                let mut total = 0;
+                let mut weight = Weight::ZERO;
+                for hoard in generator.take(30) {
+                    assert_eq!(hoard.theme, theme);
+                    if theme == Theme::Tempelschatz {
+                        assert!(
+                            hoard
+                                .items
+                                .iter()
+                                .all(|(item, _)| item.tags.iter().any(|t| t == "religioes"))
+                        );
+                    }
+                    assert!(hoard.weight() >= hoard.coins.weight());
+                    total += hoard.value().as_kreuzer();
+                    weight += hoard.weight();
+                }
+                values.push(total);
+                weights.push(weight);
+            }
+            assert!(
+                values.windows(2).all(|w| w[0] < w[1]),
+                "{theme}: {values:?}"
+            );
+            assert!(
+                weights.windows(2).all(|w| w[0] < w[1]),
+                "{theme}: {weights:?}"
+            );
+        }
+    }
+}
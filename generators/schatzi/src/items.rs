@@ -1,29 +1,451 @@
-use std::iter::Iterator;
+use std::{
+    fmt::Display,
+    io::Read,
+    iter::Iterator,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 
-use crate::money::Coins;
+use generate::RPGenerator;
+use rand::{Rng, SeedableRng, rngs::StdRng, seq::IndexedRandom};
 
-type Gram = u32;
+use crate::{
+    Stratum,
+    money::Money,
+    properties::{self, Property},
+    provenance::{Flavour, ItemCondition},
+    weight::Weight,
+};
 
+pub use crate::weight::Gram;
+
+/// for lists without a weight column, about what a knife or a purse weighs
+pub const DEFAULT_ITEM_WEIGHT: Weight = Weight::from_unzen(4);
+
+/// the item list shipped with the crate
+const BUILTIN_ITEMS: &str = include_str!("../loot_items.csv");
+
+/// column order of `loot_items.csv`, everything after `max_pp` is optional
+const COLUMNS: [&str; 7] = [
+    "handle", "name", "max_pp", "weight", "value", "rarity", "tags",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum Rarity {
+    #[default]
+    Common,
+    Uncommon,
+    Rare,
+}
+
+impl FromStr for Rarity {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "common" => Ok(Self::Common),
+            "uncommon" => Ok(Self::Uncommon),
+            "rare" => Ok(Self::Rare),
+            _ => Err(()),
+        }
+    }
+}
+
+impl Display for Rarity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::Common => "häufig",
+            Self::Uncommon => "ungewöhnlich",
+            Self::Rare => "selten",
+        };
+        write!(f, "{s}")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct LootItemRecord {
-    handle: String,
-    name: String,
-    max_pp: u8,
+    pub handle: String,
+    pub name: String,
+    /// how many of these one person carries at most
+    pub max_pp: u8,
+    /// a single piece, [`DEFAULT_ITEM_WEIGHT`] if the list has none
+    pub weight: Weight,
+    pub value: Option<Money>,
+    pub rarity: Rarity,
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug)]
+pub enum LoadError {
+    Io(std::io::Error),
+    Csv {
+        line: Option<u64>,
+        source: csv::Error,
+    },
+    MissingColumn {
+        line: u64,
+        column: &'static str,
+    },
+    InvalidValue {
+        line: u64,
+        column: &'static str,
+        value: String,
+    },
+    UnknownFormat(PathBuf),
+    #[cfg(feature = "sqlite")]
+    Sqlite(rusqlite::Error),
+}
+
+impl Display for LoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "Gegenstandsliste konnte nicht gelesen werden: {e}"),
+            Self::Csv {
+                line: Some(line),
+                source,
+            } => write!(f, "Zeile {line}: {source}"),
+            Self::Csv { line: None, source } => write!(f, "{source}"),
+            Self::MissingColumn { line, column } => {
+                write!(f, "Zeile {line}: Spalte `{column}` fehlt")
+            }
+            Self::InvalidValue {
+                line,
+                column,
+                value,
+            } => write!(
+                f,
+                "Zeile {line}: `{value}` ist kein gültiger Wert für `{column}`"
+            ),
+            Self::UnknownFormat(path) => {
+                write!(f, "{}: unbekanntes Dateiformat", path.display())
+            }
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite(e) => write!(f, "Datenbankfehler: {e}"),
+        }
+    }
 }
 
+impl std::error::Error for LoadError {}
+
+impl LootItemRecord {
+    /// `fields` in the order of [`COLUMNS`], missing or empty optional fields fall back to defaults
+    fn parse(line: u64, fields: &[Option<&str>]) -> Result<Self, LoadError> {
+        let field = |i: usize| {
+            fields
+                .get(i)
+                .copied()
+                .flatten()
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+        };
+        let required = |i: usize| {
+            field(i).ok_or(LoadError::MissingColumn {
+                line,
+                column: COLUMNS[i],
+            })
+        };
+        let invalid = |i: usize| LoadError::InvalidValue {
+            line,
+            column: COLUMNS[i],
+            value: field(i).unwrap_or_default().to_string(),
+        };
+        let number = |i: usize| {
+            field(i)
+                .map(|s| s.parse::<u32>().map_err(|_| invalid(i)))
+                .transpose()
+        };
+
+        Ok(Self {
+            handle: required(0)?.to_string(),
+            name: required(1)?.to_string(),
+            max_pp: required(2)?.parse().map_err(|_| invalid(2))?,
+            weight: number(3)?.map_or(DEFAULT_ITEM_WEIGHT, Weight::from_gram),
+            value: number(4)?.map(Money::from_kreuzer),
+            rarity: field(5)
+                .map(|s| s.parse().map_err(|_| invalid(5)))
+                .transpose()?
+                .unwrap_or_default(),
+            tags: field(6)
+                .map(|s| s.split('|').map(|t| t.trim().to_string()).collect())
+                .unwrap_or_default(),
+        })
+    }
+}
+
+/// Draws pocket contents for one person of the current stratum per call of `next`.
 pub struct LootStash {
+    rng: StdRng,
     all_items: Vec<LootItemRecord>,
+    stratum: Stratum,
 }
 impl LootStash {
-    fn new(db_path: &str) -> Self {
-        todo!("Load items from Database")
+    fn with_items(all_items: Vec<LootItemRecord>) -> Self {
+        Self {
+            rng: StdRng::from_os_rng(),
+            all_items,
+            stratum: Stratum::Normal,
+        }
+    }
+
+    /// loads `.csv` files, and SQLite databases (`.db`, `.sqlite`) with the `sqlite` feature
+    pub fn new(db_path: impl AsRef<Path>) -> Result<Self, LoadError> {
+        let path = db_path.as_ref();
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("csv") => Self::from_csv(std::fs::File::open(path).map_err(LoadError::Io)?),
+            #[cfg(feature = "sqlite")]
+            Some("db" | "sqlite" | "sqlite3") => Self::from_sqlite(path),
+            _ => Err(LoadError::UnknownFormat(path.to_path_buf())),
+        }
+    }
+
+    /// the items from `loot_items.csv`
+    pub fn builtin() -> Self {
+        Self::from_csv(BUILTIN_ITEMS.as_bytes()).expect("loot_items.csv is valid")
+    }
+
+    /// One item per line, no quoting needed. A header line and lines starting with `#` are skipped.
+    pub fn from_csv(reader: impl Read) -> Result<Self, LoadError> {
+        let mut reader = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .from_reader(reader);
+        let mut all_items = vec![];
+        for record in reader.records() {
+            let record = record.map_err(|source| LoadError::Csv {
+                line: source.position().map(|p| p.line()),
+                source,
+            })?;
+            let line = record.position().map_or(0, |p| p.line());
+            // csv's own comment support would throw off the line numbers
+            if record.get(0) == Some(COLUMNS[0])
+                || record.get(0).is_some_and(|h| h.starts_with('#'))
+            {
+                continue;
+            }
+            let fields: Vec<Option<&str>> = record.iter().map(Some).collect();
+            all_items.push(LootItemRecord::parse(line, &fields)?);
+        }
+        Ok(Self::with_items(all_items))
+    }
+
+    /// Reads the table `loot_items` with the same columns as the CSV file.
+    /// Errors name the row number instead of a line.
+    #[cfg(feature = "sqlite")]
+    pub fn from_sqlite(path: impl AsRef<Path>) -> Result<Self, LoadError> {
+        let conn =
+            rusqlite::Connection::open_with_flags(path, rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY)
+                .map_err(LoadError::Sqlite)?;
+        let query = format!(
+            "SELECT {} FROM loot_items",
+            COLUMNS.map(|c| format!("CAST({c} AS TEXT)")).join(", ")
+        );
+        let mut stmt = conn.prepare(&query).map_err(LoadError::Sqlite)?;
+        let mut rows = stmt.query([]).map_err(LoadError::Sqlite)?;
+        let mut all_items = vec![];
+        let mut line = 0;
+        while let Some(row) = rows.next().map_err(LoadError::Sqlite)? {
+            line += 1;
+            let fields = (0..COLUMNS.len())
+                .map(|i| row.get::<_, Option<String>>(i))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(LoadError::Sqlite)?;
+            let fields: Vec<Option<&str>> = fields.iter().map(Option::as_deref).collect();
+            all_items.push(LootItemRecord::parse(line, &fields)?);
+        }
+        Ok(Self::with_items(all_items))
+    }
+
+    pub fn items(&self) -> &[LootItemRecord] {
+        &self.all_items
+    }
+
+    pub fn set_stratum(&mut self, stratum: Stratum) {
+        self.stratum = stratum;
+    }
+
+    /// how likely `item` ends up in the pockets of someone from `stratum`, 0 if never
+    fn weight(stratum: Stratum, item: &LootItemRecord) -> u32 {
+        let too_expensive = item
+            .value
+            .is_some_and(|v| stratum.max_item_value().is_some_and(|max| v > max));
+        if too_expensive {
+            return 0;
+        }
+        match (stratum, item.rarity) {
+            (_, Rarity::Common) => 6,
+            (Stratum::Rich, Rarity::Uncommon) => 4,
+            (Stratum::Rich, Rarity::Rare) => 2,
+            (Stratum::Normal, Rarity::Uncommon) => 2,
+            (Stratum::Normal, Rarity::Rare) => 1,
+            (Stratum::Poor, Rarity::Uncommon) => 1,
+            _ => 0,
+        }
+    }
+}
+
+/// one item from the stash as someone carries it
+#[derive(Debug, Clone, PartialEq)]
+pub struct LootItem {
+    pub record: LootItemRecord,
+    pub amount: u32,
+    /// blessings, curses and enchantments, only ever on a single piece
+    pub properties: Vec<Property>,
+    pub flavour: Flavour,
+}
+
+impl LootItem {
+    /// list price of all pieces in their condition, None if the item has no value
+    pub fn value(&self) -> Option<Money> {
+        Some((self.record.value? * self.amount).percent(self.flavour.condition.percent()))
+    }
+
+    /// what the piece looks like beyond its name: properties, provenance and marks
+    pub fn details(&self) -> Vec<String> {
+        let mut details: Vec<String> = self.properties.iter().map(Property::to_string).collect();
+        details.extend(self.flavour.provenance.map(|p| p.to_string()));
+        details.extend(self.flavour.marks.iter().map(|m| format!("hat {m}")));
+        details
+    }
+}
+
+impl Display for LootItem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.amount != 1 {
+            write!(f, "{}x ", self.amount)?;
+        }
+        write!(f, "{}", self.record.name)?;
+        if self.flavour.condition != ItemCondition::New {
+            write!(f, " ({})", self.flavour.condition)?;
+        }
+        Ok(())
     }
 }
 
-pub struct PersonLootItems(Vec<(LootItemRecord, u32)>);
+/// the items a person carries
+pub struct PersonLootItems(pub Vec<LootItem>);
+
+impl PersonLootItems {
+    pub fn weight(&self) -> Weight {
+        self.0
+            .iter()
+            .map(|item| item.record.weight * item.amount)
+            .sum()
+    }
+
+    /// items with properties, a provenance or marks
+    pub fn special(&self) -> impl Iterator<Item = &LootItem> {
+        self.0.iter().filter(|item| !item.details().is_empty())
+    }
+}
 
 impl Iterator for LootStash {
     type Item = PersonLootItems;
     fn next(&mut self) -> Option<PersonLootItems> {
-        todo!()
+        let (min, max) = self.stratum.item_count();
+        let count = self.rng.random_range(min..=max);
+        let candidates: Vec<&LootItemRecord> = self
+            .all_items
+            .iter()
+            .filter(|item| item.max_pp > 0 && Self::weight(self.stratum, item) > 0)
+            .collect();
+        let mut items = vec![];
+        for item in candidates
+            .choose_multiple_weighted(&mut self.rng, count, |item| {
+                Self::weight(self.stratum, item)
+            })
+            .ok()?
+        {
+            // mostly one, sometimes a handful up to max_pp
+            let amount = if self.rng.random_bool(0.7) {
+                1
+            } else {
+                self.rng.random_range(1..=item.max_pp as u32)
+            };
+            // a handful of figurines is rarely all blessed
+            let properties = if amount == 1 {
+                properties::roll(&mut self.rng, item)
+            } else {
+                vec![]
+            };
+            items.push(LootItem {
+                record: (*item).clone(),
+                amount,
+                properties,
+                flavour: Flavour::roll(&mut self.rng, item, self.stratum, amount),
+            });
+        }
+        Some(PersonLootItems(items))
+    }
+}
+
+impl RPGenerator for LootStash {
+    type Seed = u64;
+    fn seed(&mut self, s: Self::Seed) {
+        self.rng = StdRng::seed_from_u64(s);
+    }
+}
+
+impl Display for PersonLootItems {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.0.is_empty() {
+            return write!(f, "nichts");
+        }
+        let items: Vec<String> = self.0.iter().map(LootItem::to_string).collect();
+        write!(f, "{}", items.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{DEFAULT_ITEM_WEIGHT, LoadError, LootStash, Rarity};
+    use crate::weight::Weight;
+
+    #[test]
+    fn loads_csv() {
+        let stash = LootStash::builtin();
+        assert_eq!(stash.items().len(), 81);
+        let knife = &stash.items()[0];
+        assert_eq!(knife.handle, "knife");
+        assert_eq!(knife.weight, Weight::from_gram(100));
+        assert_eq!(knife.tags, ["waffe", "werkzeug"]);
+
+        // old files without the extra columns still load
+        let stash = LootStash::from_csv("torch,Fackel,1\n".as_bytes()).unwrap();
+        assert_eq!(stash.items()[0].rarity, Rarity::Common);
+        assert_eq!(stash.items()[0].value, None);
+        assert_eq!(stash.items()[0].weight, DEFAULT_ITEM_WEIGHT);
+
+        let broken = "knife,Messer,2\n# kommentar\ntorch,Fackel,viele\n";
+        match LootStash::from_csv(broken.as_bytes()) {
+            Err(LoadError::InvalidValue { line, column, .. }) => {
+                assert_eq!((line, column), (3, "max_pp"))
+            }
+            other => panic!("expected an invalid max_pp, got {:?}", other.err()),
+        }
+        assert!(matches!(
+            LootStash::from_csv("knife\n".as_bytes()),
+            Err(LoadError::MissingColumn { line: 1, .. })
+        ));
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn loads_sqlite() {
+        let path = std::env::temp_dir().join(format!("schatzi-{}.db", std::process::id()));
+        let conn = rusqlite::Connection::open(&path).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE loot_items (handle TEXT, name TEXT, max_pp INTEGER, weight INTEGER,
+                value INTEGER, rarity TEXT, tags TEXT);
+             INSERT INTO loot_items VALUES ('torch', 'Fackel', 1, 500, 10, 'common', 'licht');
+             INSERT INTO loot_items VALUES ('gem_ruby', 'Kleiner Rubin', 1, NULL, NULL, 'rare', NULL);",
+        )
+        .unwrap();
+        drop(conn);
+        let stash = LootStash::new(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(stash.items().len(), 2);
+        assert_eq!(stash.items()[0].weight, Weight::from_gram(500));
+        assert_eq!(stash.items()[1].rarity, Rarity::Rare);
+        assert_eq!(stash.items()[1].weight, DEFAULT_ITEM_WEIGHT);
+        assert!(stash.items()[1].tags.is_empty());
     }
 }
@@ -1,54 +1,238 @@
 use std::fmt::{Debug, Display};
 
+use generate::RPGenerator;
+use rand::{Rng, SeedableRng, rngs::StdRng, seq::IndexedRandom};
+
 use crate::{
+    clothing::{Outfit, Region, Season},
+    currency::Currency,
     items::{LootStash, PersonLootItems},
-    money::Coins,
+    money::{Coins, Money},
+    weight::Weight,
 };
 
-mod items;
-mod money;
+pub mod clothing;
+pub mod currency;
+pub mod fence;
+pub mod hoard;
+pub mod items;
+pub mod market;
+pub mod money;
+pub mod properties;
+pub mod provenance;
+pub mod weight;
 
-enum Stratum {
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Stratum {
     Rich,
     Normal,
     Poor,
     Beggar,
 }
 
-struct PersonConfig {
-    stratum: Stratum,
+impl Stratum {
+    pub const ALL: [Stratum; 4] = [Self::Rich, Self::Normal, Self::Poor, Self::Beggar];
+
+    /// how many different items someone carries
+    pub fn item_count(self) -> (usize, usize) {
+        match self {
+            Self::Rich => (2, 5),
+            Self::Normal => (1, 3),
+            Self::Poor => (0, 2),
+            Self::Beggar => (0, 1),
+        }
+    }
+
+    /// nobody of this stratum carries anything worth more, None for no limit
+    pub fn max_item_value(self) -> Option<Money> {
+        match self {
+            Self::Rich => None,
+            Self::Normal => Some(Money::from_kreuzer(10000)),
+            Self::Poor => Some(Money::from_kreuzer(1000)),
+            Self::Beggar => Some(Money::from_kreuzer(100)),
+        }
+    }
+
+    /// coins in the purse in Kreuzer
+    pub fn purse(self) -> (u32, u32) {
+        match self {
+            Self::Rich => (5000, 50000),
+            Self::Normal => (500, 5000),
+            Self::Poor => (50, 500),
+            Self::Beggar => (0, 50),
+        }
+    }
+
+    /// share of a city crowd, think Gareth on market day
+    fn passer_by_weight(self) -> u32 {
+        match self {
+            Self::Rich => 10,
+            Self::Normal => 55,
+            Self::Poor => 25,
+            Self::Beggar => 10,
+        }
+    }
+}
+
+impl Display for Stratum {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::Rich => "wohlhabend",
+            Self::Normal => "einfaches Volk",
+            Self::Poor => "arm",
+            Self::Beggar => "Bettler",
+        };
+        write!(f, "{s}")
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PersonConfig {
+    /// None picks a random passer-by
+    pub stratum: Option<Stratum>,
+    /// the coins people carry where the theft happens
+    pub currency: Currency,
+    /// decides the local fashion
+    pub region: Region,
+    pub season: Season,
 }
 
-struct Schatzi {
+pub struct Schatzi {
+    rng: StdRng,
     loot_stash: LootStash,
     person_config: PersonConfig,
 }
 
 impl Schatzi {
-    fn new() -> Self {
-        todo!()
+    pub fn new(person_config: PersonConfig) -> Self {
+        Self::with_stash(LootStash::builtin(), person_config)
+    }
+    pub fn with_stash(loot_stash: LootStash, person_config: PersonConfig) -> Self {
+        Self {
+            rng: StdRng::from_os_rng(),
+            loot_stash,
+            person_config,
+        }
     }
 }
 
-impl generate::RPGenerator for Schatzi {
+impl RPGenerator for Schatzi {
     type Seed = u64;
-    fn seed(&mut self, s: Self::Seed) {}
+    fn seed(&mut self, s: Self::Seed) {
+        self.rng = StdRng::seed_from_u64(s);
+        self.loot_stash.seed(s.wrapping_add(1));
+    }
 }
 
-struct PersonLoot {
-    items: PersonLootItems,
-    coins: Coins,
-    // todo: clothing
+pub struct PersonLoot {
+    pub stratum: Stratum,
+    pub items: PersonLootItems,
+    pub coins: Coins,
+    pub outfit: Outfit,
+}
+impl PersonLoot {
+    /// purse and pockets, the clothes stay on the victim
+    pub fn weight(&self) -> Weight {
+        self.coins.weight() + self.items.weight()
+    }
 }
 impl Display for PersonLoot {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        todo!()
+        writeln!(f, "Opfer: {}", self.stratum)?;
+        writeln!(f, "Kleidung: {}", self.outfit)?;
+        writeln!(f, "Geldbeutel: {}", self.coins)?;
+        writeln!(f, "Taschen: {}", self.items)?;
+        for item in self.items.special() {
+            writeln!(f, "  * {}: {}", item.record.name, item.details().join(", "))?;
+            for hook in item.flavour.hooks() {
+                writeln!(f, "    Beim Hehler: {hook}")?;
+            }
+        }
+        write!(f, "Gewicht: {}", self.weight())
     }
 }
 
 impl Iterator for Schatzi {
     type Item = PersonLoot;
     fn next(&mut self) -> Option<Self::Item> {
-        todo!()
+        let stratum = match self.person_config.stratum {
+            Some(s) => s,
+            None => *Stratum::ALL
+                .choose_weighted(&mut self.rng, |s| s.passer_by_weight())
+                .ok()?,
+        };
+        self.loot_stash.set_stratum(stratum);
+        let items = self.loot_stash.next()?;
+        let (min, max) = stratum.purse();
+        let coins = Coins::new_random_in(
+            Money::from_kreuzer(self.rng.random_range(min..=max)),
+            self.person_config.currency,
+            &mut self.rng,
+        );
+        let outfit = Outfit::random(
+            &mut self.rng,
+            stratum,
+            self.person_config.region,
+            self.person_config.season,
+        );
+        Some(PersonLoot {
+            stratum,
+            items,
+            coins,
+            outfit,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use generate::RPGenerator;
+
+    use crate::{PersonConfig, Schatzi, Stratum, items::LootItem, money::Money};
+
+    #[test]
+    fn loot_depends_on_stratum() {
+        let mut mean_items = vec![];
+        for stratum in Stratum::ALL {
+            let mut schatzi = Schatzi::new(PersonConfig {
+                stratum: Some(stratum),
+                ..Default::default()
+            });
+            schatzi.seed(7);
+            let mut items = 0;
+            for loot in schatzi.by_ref().take(200) {
+                assert_eq!(loot.stratum, stratum);
+                let (min, max) = stratum.purse();
+                let purse = Money::from(loot.coins).as_kreuzer();
+                assert!((min as u64..=max as u64).contains(&purse));
+                for (
+                    i,
+                    LootItem {
+                        record: item,
+                        amount: n,
+                        properties,
+                        flavour,
+                    },
+                ) in loot.items.0.iter().enumerate()
+                {
+                    assert!(*n >= 1 && *n <= item.max_pp as u32, "{}", item.name);
+                    assert!(*n == 1 || properties.is_empty() && flavour.provenance.is_none());
+                    assert!(
+                        stratum
+                            .max_item_value()
+                            .is_none_or(|max| item.value.is_none_or(|v| v <= max))
+                    );
+                    // every item shows up at most once
+                    assert!(
+                        loot.items.0[i + 1..]
+                            .iter()
+                            .all(|o| o.record.handle != item.handle)
+                    );
+                }
+                items += loot.items.0.len();
+            }
+            mean_items.push(items);
+        }
+        assert!(mean_items.windows(2).all(|w| w[0] > w[1]), "{mean_items:?}");
     }
 }
@@ -0,0 +1,299 @@
+//! Merchants' stock with prices, based on the item values from `loot_items.csv`.
+
+use std::fmt::Display;
+
+use generate::{
+    RPGenerator,
+    dsa5::{Probe, probe},
+};
+use rand::{Rng, SeedableRng, rngs::StdRng};
+
+use crate::{
+    currency::Currency,
+    items::{LootItemRecord, LootStash, Rarity},
+    money::Money,
+};
+
+/// every QS the buyer is ahead of the merchant moves the price by this many percent
+pub(crate) const PERCENT_PER_QS: i32 = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum SettlementSize {
+    Village,
+    #[default]
+    Town,
+    City,
+    Metropolis,
+}
+
+impl SettlementSize {
+    pub const ALL: [SettlementSize; 4] = [Self::Village, Self::Town, Self::City, Self::Metropolis];
+
+    /// chance in percent that a merchant has an item of this rarity
+    fn availability(self, rarity: Rarity) -> u32 {
+        match (self, rarity) {
+            (Self::Village, Rarity::Common) => 60,
+            (Self::Village, Rarity::Uncommon) => 15,
+            (Self::Village, Rarity::Rare) => 2,
+            (Self::Town, Rarity::Common) => 75,
+            (Self::Town, Rarity::Uncommon) => 30,
+            (Self::Town, Rarity::Rare) => 8,
+            (Self::City, Rarity::Common) => 90,
+            (Self::City, Rarity::Uncommon) => 50,
+            (Self::City, Rarity::Rare) => 20,
+            (Self::Metropolis, Rarity::Common) => 95,
+            (Self::Metropolis, Rarity::Uncommon) => 70,
+            (Self::Metropolis, Rarity::Rare) => 35,
+        }
+    }
+
+    /// price in percent of the list price, whatever is hard to get is expensive
+    fn price_percent(self, rarity: Rarity) -> u64 {
+        match (self, rarity) {
+            (Self::Village, Rarity::Common) => 100,
+            (Self::Village, _) => 150,
+            (Self::Town, Rarity::Rare) => 120,
+            (Self::Town, _) => 100,
+            (Self::City, _) => 100,
+            (Self::Metropolis, Rarity::Common) => 90,
+            (Self::Metropolis, _) => 100,
+        }
+    }
+
+    /// most pieces of one item in stock
+    fn max_stock(self) -> u32 {
+        match self {
+            Self::Village => 2,
+            Self::Town => 4,
+            Self::City => 6,
+            Self::Metropolis => 10,
+        }
+    }
+}
+
+impl Display for SettlementSize {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::Village => "Dorf",
+            Self::Town => "Kleinstadt",
+            Self::City => "Stadt",
+            Self::Metropolis => "Großstadt",
+        };
+        write!(f, "{s}")
+    }
+}
+
+/// regional price in percent, for goods that are frowned upon or plentiful somewhere
+fn regional_percent(currency: Currency, item: &LootItemRecord) -> u64 {
+    let tagged = |tag: &str| item.tags.iter().any(|t| t == tag);
+    match currency {
+        Currency::Kalifat if tagged("alkohol") => 300,
+        Currency::Zwergenreiche if tagged("edelstein") || tagged("schmuck") => 80,
+        Currency::Horasreich if tagged("schrift") => 80,
+        Currency::AlAnfa if tagged("gift") => 70,
+        _ => 100,
+    }
+}
+
+/// Handel (Feilschen) values of whoever haggles
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Haggler {
+    /// KL, IN, CH
+    pub eigenschaften: [u8; 3],
+    pub fw: u8,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MarketConfig {
+    pub settlement: SettlementSize,
+    pub currency: Currency,
+    /// None buys at the asking price
+    pub haggler: Option<Haggler>,
+}
+
+/// the outcome of the Feilschen check, compared against the merchant's
+#[derive(Debug, Clone, Copy)]
+pub struct Haggling {
+    pub buyer: Probe,
+    pub merchant: Probe,
+    /// change of all prices, negative is cheaper
+    pub percent: i32,
+}
+
+/// A Feilschen check against a merchant of `settlement`.
+/// Merchants in big cities know their trade.
+pub(crate) fn haggle(rng: &mut impl Rng, settlement: SettlementSize, buyer: Haggler) -> Haggling {
+    let merchant_fw = match settlement {
+        SettlementSize::Village => rng.random_range(2..=6),
+        SettlementSize::Town => rng.random_range(4..=8),
+        SettlementSize::City => rng.random_range(6..=10),
+        SettlementSize::Metropolis => rng.random_range(8..=12),
+    };
+    let merchant_eigenschaften = std::array::from_fn(|_| rng.random_range(11..=14));
+    let merchant = probe(rng, merchant_eigenschaften, merchant_fw, 0);
+    let buyer = probe(rng, buyer.eigenschaften, buyer.fw, 0);
+    let qs = |p: &Probe| p.qs.unwrap_or(0) as i32;
+    Haggling {
+        buyer,
+        merchant,
+        percent: -PERCENT_PER_QS * (qs(&buyer) - qs(&merchant)),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Offer {
+    pub item: LootItemRecord,
+    pub stock: u32,
+    /// what the merchant asks for
+    pub asking_price: Money,
+    /// after haggling
+    pub price: Money,
+}
+
+#[derive(Debug, Clone)]
+pub struct MerchantStock {
+    pub settlement: SettlementSize,
+    pub currency: Currency,
+    pub haggling: Option<Haggling>,
+    pub offers: Vec<Offer>,
+}
+
+pub struct MarketGenerator {
+    rng: StdRng,
+    items: Vec<LootItemRecord>,
+    config: MarketConfig,
+}
+
+impl MarketGenerator {
+    pub fn new(config: MarketConfig) -> Self {
+        Self::with_stash(&LootStash::builtin(), config)
+    }
+    /// only items with a value can be sold
+    pub fn with_stash(stash: &LootStash, config: MarketConfig) -> Self {
+        Self {
+            rng: StdRng::from_os_rng(),
+            items: stash
+                .items()
+                .iter()
+                .filter(|item| item.value.is_some())
+                .cloned()
+                .collect(),
+            config,
+        }
+    }
+}
+
+impl Iterator for MarketGenerator {
+    type Item = MerchantStock;
+    fn next(&mut self) -> Option<Self::Item> {
+        let MarketConfig {
+            settlement,
+            currency,
+            haggler,
+        } = self.config;
+        let haggling = haggler.map(|h| haggle(&mut self.rng, settlement, h));
+        let percent = 100 + haggling.map_or(0, |h| h.percent);
+        let mut offers = vec![];
+        for item in &self.items {
+            if self.rng.random_range(0..100) >= settlement.availability(item.rarity) {
+                continue;
+            }
+            let list_price = item.value.unwrap_or(Money::ZERO);
+            let asking_price = list_price
+                .percent(settlement.price_percent(item.rarity))
+                .percent(regional_percent(currency, item));
+            offers.push(Offer {
+                item: item.clone(),
+                stock: self.rng.random_range(1..=settlement.max_stock()),
+                asking_price,
+                price: asking_price.percent(percent as u64),
+            });
+        }
+        Some(MerchantStock {
+            settlement,
+            currency,
+            haggling,
+            offers,
+        })
+    }
+}
+
+impl RPGenerator for MarketGenerator {
+    type Seed = u64;
+    fn seed(&mut self, s: Self::Seed) {
+        self.rng = StdRng::seed_from_u64(s);
+    }
+}
+
+impl Display for MerchantStock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Händler, {} ({})", self.settlement, self.currency)?;
+        if let Some(h) = &self.haggling {
+            let qs = |p: &Probe| {
+                p.qs.map_or("misslungen".to_string(), |qs| format!("QS {qs}"))
+            };
+            writeln!(
+                f,
+                "Feilschen: {} gegen {}, Preise {:+}%",
+                qs(&h.buyer),
+                qs(&h.merchant),
+                h.percent
+            )?;
+        }
+        writeln!(
+            f,
+            "{:<34} {:>6}  {:<36} Preis",
+            "Ware", "Anzahl", "Verlangt"
+        )?;
+        for offer in &self.offers {
+            writeln!(
+                f,
+                "{:<34} {:>6}  {:<36} {}",
+                offer.item.name,
+                offer.stock,
+                offer.asking_price.in_currency(self.currency).to_string(),
+                offer.price.in_currency(self.currency)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use generate::RPGenerator;
+
+    use super::{Haggler, MarketConfig, MarketGenerator, SettlementSize};
+    use crate::items::Rarity;
+
+    #[test]
+    fn bigger_markets_have_more() {
+        let mut offers = vec![];
+        for settlement in SettlementSize::ALL {
+            let mut generator = MarketGenerator::new(MarketConfig {
+                settlement,
+                haggler: Some(Haggler {
+                    eigenschaften: [14, 14, 14],
+                    fw: 10,
+                }),
+                ..Default::default()
+            });
+            generator.seed(5);
+            let mut count = 0;
+            for stock in generator.take(50) {
+                let haggling = stock.haggling.unwrap();
+                for offer in &stock.offers {
+                    assert!(offer.stock >= 1);
+                    let expected = offer.asking_price.percent((100 + haggling.percent) as u64);
+                    assert_eq!(offer.price, expected);
+                    if offer.item.rarity == Rarity::Common && settlement == SettlementSize::City {
+                        assert_eq!(Some(offer.asking_price), offer.item.value);
+                    }
+                }
+                count += stock.offers.len();
+            }
+            offers.push(count);
+        }
+        assert!(offers.windows(2).all(|w| w[0] < w[1]), "{offers:?}");
+    }
+}
@@ -1,27 +1,80 @@
-use std::fmt::{Debug, Display};
+use std::{
+    fmt::{Debug, Display},
+    iter::Sum,
+    ops::{Add, AddAssign, Mul, Sub, SubAssign},
+    str::FromStr,
+};
 
+use rand::Rng;
+use serde::{Deserialize, Serialize, de::Visitor};
+
+use crate::{
+    currency::{Currency, Denomination, MAX_DENOMINATIONS},
+    weight::{COIN_WEIGHT, Weight},
+};
+
+/// an empty purse, lower case so parsing can compare against it
+const NO_COINS: &str = "keine Münzen";
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
 pub struct Money {
     kreuzer: u64,
 }
 impl Money {
+    pub const ZERO: Money = Money { kreuzer: 0 };
+
+    pub fn as_kreuzer(&self) -> u64 {
+        self.kreuzer
+    }
     pub fn from_kreuzer(kreuzer: u32) -> Self {
         Self {
             kreuzer: kreuzer as u64,
         }
     }
-    pub fn from_heller(heller: f32) -> Self {
+    pub fn from_heller(heller: u32) -> Self {
+        Self {
+            kreuzer: heller as u64 * 10,
+        }
+    }
+    pub fn from_silber(silber: u32) -> Self {
+        Self {
+            kreuzer: silber as u64 * 100,
+        }
+    }
+    pub fn from_dukaten(dukaten: u32) -> Self {
         Self {
-            kreuzer: (heller * 10.).floor() as u64,
+            kreuzer: dukaten as u64 * 1000,
         }
     }
-    pub fn from_silber(silber: f32) -> Self {
+    pub fn checked_add(self, rhs: Money) -> Option<Money> {
+        Some(Self {
+            kreuzer: self.kreuzer.checked_add(rhs.kreuzer)?,
+        })
+    }
+    /// None if `rhs` is more than we have
+    pub fn checked_sub(self, rhs: Money) -> Option<Money> {
+        Some(Self {
+            kreuzer: self.kreuzer.checked_sub(rhs.kreuzer)?,
+        })
+    }
+    pub fn checked_mul(self, quantity: u32) -> Option<Money> {
+        Some(Self {
+            kreuzer: self.kreuzer.checked_mul(quantity as u64)?,
+        })
+    }
+    pub fn saturating_sub(self, rhs: Money) -> Money {
         Self {
-            kreuzer: (silber * 100.).floor() as u64,
+            kreuzer: self.kreuzer.saturating_sub(rhs.kreuzer),
         }
     }
-    pub fn from_dukaten(dukaten: f32) -> Self {
+    /// the fewest coins of `currency`, for showing prices in the local coinage
+    pub fn in_currency(self, currency: Currency) -> Coins {
+        Coins::exact(self, currency)
+    }
+    /// `percent` of the amount, rounded down to whole Kreuzer
+    pub fn percent(self, percent: u64) -> Money {
         Self {
-            kreuzer: (dukaten * 1000.).floor() as u64,
+            kreuzer: (self.kreuzer as u128 * percent as u128 / 100) as u64,
         }
     }
 }
@@ -57,26 +110,364 @@ impl Debug for Money {
     }
 }
 
+impl Add for Money {
+    type Output = Money;
+    fn add(self, rhs: Money) -> Money {
+        self.checked_add(rhs).expect("Money overflow")
+    }
+}
+impl AddAssign for Money {
+    fn add_assign(&mut self, rhs: Money) {
+        *self = *self + rhs;
+    }
+}
+/// panics if `rhs` is more than we have, use [`Money::checked_sub`] when that can happen
+impl Sub for Money {
+    type Output = Money;
+    fn sub(self, rhs: Money) -> Money {
+        self.checked_sub(rhs).expect("Money cannot become negative")
+    }
+}
+impl SubAssign for Money {
+    fn sub_assign(&mut self, rhs: Money) {
+        *self = *self - rhs;
+    }
+}
+/// price times quantity
+impl Mul<u32> for Money {
+    type Output = Money;
+    fn mul(self, quantity: u32) -> Money {
+        self.checked_mul(quantity).expect("Money overflow")
+    }
+}
+impl Sum for Money {
+    fn sum<I: Iterator<Item = Money>>(iter: I) -> Money {
+        iter.fold(Money::ZERO, Add::add)
+    }
+}
+impl<'a> Sum<&'a Money> for Money {
+    fn sum<I: Iterator<Item = &'a Money>>(iter: I) -> Money {
+        iter.copied().sum()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseMoneyError {
+    Empty,
+    InvalidNumber(String),
+    UnknownCoin(String),
+    /// a number without a coin after it
+    MissingCoin(String),
+    Overflow,
+}
+
+impl Display for ParseMoneyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => write!(f, "kein Betrag angegeben"),
+            Self::InvalidNumber(n) => write!(f, "`{n}` ist keine gültige Anzahl"),
+            Self::UnknownCoin(c) => write!(f, "unbekannte Münze `{c}`"),
+            Self::MissingCoin(n) => write!(f, "nach `{n}` fehlt die Münze"),
+            Self::Overflow => write!(f, "Betrag ist zu groß"),
+        }
+    }
+}
+
+impl std::error::Error for ParseMoneyError {}
+
+/// value of a coin in Kreuzer, by abbreviation or name, including foreign coins
+fn coin_value(name: &str) -> Option<u64> {
+    let name = name.to_lowercase();
+    match name.as_str() {
+        "d" => Some(1000),
+        "s" | "st" | "silber" | "taler" => Some(100),
+        "h" => Some(10),
+        "k" => Some(1),
+        _ => Currency::ALL
+            .iter()
+            .flat_map(|c| c.denominations())
+            .find(|coin| coin.name.to_lowercase() == name || coin.plural.to_lowercase() == name)
+            .map(|coin| coin.kreuzer),
+    }
+}
+
+/// `count` coins worth `value` Kreuzer each, `count` may have a decimal comma ("2,5")
+/// and dots between groups of thousands ("1.000"), so "1.5" is no number at all
+fn parse_count(count: &str, value: u64) -> Result<u64, ParseMoneyError> {
+    let invalid = || ParseMoneyError::InvalidNumber(count.to_string());
+    let (whole, fraction) = count.split_once(',').unwrap_or((count, ""));
+    let mut groups = whole.split('.');
+    let first = groups.next().unwrap_or_default();
+    if (whole.contains('.') && !(1..=3).contains(&first.len())) || groups.any(|g| g.len() != 3) {
+        return Err(invalid());
+    }
+    let whole = whole.replace('.', "");
+    let whole: u64 = if whole.is_empty() {
+        0
+    } else {
+        whole.parse().map_err(|_| invalid())?
+    };
+    let mut kreuzer = whole.checked_mul(value).ok_or(ParseMoneyError::Overflow)?;
+    let mut place = value;
+    for digit in fraction.chars() {
+        let digit = digit.to_digit(10).ok_or_else(invalid)? as u64;
+        // fractions must come out as whole Kreuzer
+        if digit != 0 && !place.is_multiple_of(10) {
+            return Err(invalid());
+        }
+        place /= 10;
+        kreuzer += digit * place;
+    }
+    Ok(kreuzer)
+}
+
+/// Accepts DSA notation like "3 D 2 S" or "3D 2S 5H" as well as
+/// "3 Dukaten und 2 Silbertaler", decimals like "2,5 S" and thousands like "1.000 D".
+/// Whatever [`Coins`] prints can be read back, "keine Münzen" included.
+impl FromStr for Money {
+    type Err = ParseMoneyError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.trim().to_lowercase() == NO_COINS {
+            return Ok(Money::ZERO);
+        }
+        // split "3D" into "3" and "D", drop fillers
+        let mut tokens: Vec<String> = vec![];
+        for word in s.split_whitespace() {
+            let word = word.trim_end_matches(',');
+            if word.eq_ignore_ascii_case("und") || word.is_empty() {
+                continue;
+            }
+            let split = word
+                .find(|c: char| c.is_alphabetic())
+                .filter(|&i| i > 0)
+                .unwrap_or(word.len());
+            let (count, coin) = word.split_at(split);
+            tokens.push(count.to_string());
+            if !coin.is_empty() {
+                tokens.push(coin.to_string());
+            }
+        }
+        if tokens.is_empty() {
+            return Err(ParseMoneyError::Empty);
+        }
+        // coin names may have more than one word, the longest one that fits wins
+        let max_words = Currency::ALL
+            .iter()
+            .flat_map(|c| c.denominations())
+            .flat_map(|coin| [coin.name, coin.plural])
+            .map(|name| name.split_whitespace().count())
+            .max()
+            .unwrap_or(1);
+        let mut kreuzer: u64 = 0;
+        let mut i = 0;
+        while i < tokens.len() {
+            let count = &tokens[i];
+            if !count.starts_with(|c: char| c.is_ascii_digit() || c == ',' || c == '.') {
+                return Err(ParseMoneyError::InvalidNumber(count.clone()));
+            }
+            let rest = &tokens[i + 1..];
+            if rest.is_empty() {
+                return Err(ParseMoneyError::MissingCoin(count.clone()));
+            }
+            let (words, value) = (1..=max_words.min(rest.len()))
+                .rev()
+                .find_map(|n| coin_value(&rest[..n].join(" ")).map(|value| (n, value)))
+                .ok_or_else(|| ParseMoneyError::UnknownCoin(rest[0].clone()))?;
+            kreuzer = kreuzer
+                .checked_add(parse_count(count, value)?)
+                .ok_or(ParseMoneyError::Overflow)?;
+            i += 1 + words;
+        }
+        Ok(Self { kreuzer })
+    }
+}
+
+/// written in DSA notation so files stay readable
+impl Serialize for Money {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// DSA notation or a plain number of Kreuzer
+impl<'de> Deserialize<'de> for Money {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct MoneyVisitor;
+        impl Visitor<'_> for MoneyVisitor {
+            type Value = Money;
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "an amount like \"3 D 2 S\" or a number of Kreuzer")
+            }
+            fn visit_u64<E: serde::de::Error>(self, v: u64) -> Result<Money, E> {
+                Ok(Money { kreuzer: v })
+            }
+            fn visit_i64<E: serde::de::Error>(self, v: i64) -> Result<Money, E> {
+                u64::try_from(v)
+                    .map(|kreuzer| Money { kreuzer })
+                    .map_err(|_| E::custom("Money cannot be negative"))
+            }
+            fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Money, E> {
+                v.parse().map_err(E::custom)
+            }
+        }
+        deserializer.deserialize_any(MoneyVisitor)
+    }
+}
+
+/// A handful of coins of one currency
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub struct Coins {
-    d: u16,
-    s: u16,
-    h: u16,
-    k: u16,
+    currency: Currency,
+    /// same order as `currency.denominations()`
+    counts: [u32; MAX_DENOMINATIONS],
 }
 
 impl Coins {
-    pub fn new_random(amount: Money) -> Self {
-        todo!("Random Coin distribution summing to <amount>")
+    pub fn currency(&self) -> Currency {
+        self.currency
+    }
+
+    /// every kind of coin with how many there are, largest first
+    pub fn counts(&self) -> impl Iterator<Item = (Denomination, u32)> + '_ {
+        self.currency
+            .denominations()
+            .iter()
+            .copied()
+            .zip(self.counts)
+    }
+
+    /// Mittelreich coins, see [`Coins::new_random_in`]
+    pub fn new_random(amount: Money, rng: &mut impl Rng) -> Self {
+        Self::new_random_in(amount, Currency::Mittelreich, rng)
+    }
+
+    /// Nobody carries the fewest possible coins: up to two of each larger coin get changed into
+    /// the next smaller one. Small amounts thus end up as a handful of small coins,
+    /// large ones still mostly as the largest. Always sums up to exactly `amount`.
+    pub fn new_random_in(amount: Money, currency: Currency, rng: &mut impl Rng) -> Self {
+        Self::split(amount, currency, |max| rng.random_range(0..=max.min(2)))
+    }
+
+    /// the fewest coins for the amount
+    pub fn exact(amount: Money, currency: Currency) -> Self {
+        Self::split(amount, currency, |_| 0)
+    }
+
+    /// see [`COIN_WEIGHT`]
+    pub fn weight(&self) -> Weight {
+        COIN_WEIGHT * self.counts.iter().sum::<u32>()
+    }
+
+    /// `changed` says how many of the possible coins of one kind are paid in smaller coins instead
+    fn split(amount: Money, currency: Currency, mut changed: impl FnMut(u64) -> u64) -> Self {
+        let denominations = currency.denominations();
+        let mut counts = [0; MAX_DENOMINATIONS];
+        let mut rest = amount.kreuzer;
+        for (i, coin) in denominations.iter().enumerate() {
+            let max = rest / coin.kreuzer;
+            // the smallest coin takes whatever is left, and nobody changes
+            // into a heap of much smaller coins
+            let n = match denominations.get(i + 1) {
+                Some(next) if next.kreuzer * 10 >= coin.kreuzer => max - changed(max),
+                _ => max,
+            };
+            rest -= n * coin.kreuzer;
+            counts[i] = n as u32;
+        }
+        Self { currency, counts }
     }
 }
 
 impl From<Coins> for Money {
     fn from(value: Coins) -> Self {
-        return Self {
-            kreuzer: value.d as u64 * 1000
-                + value.s as u64 * 100
-                + value.h as u64 * 10
-                + value.k as u64,
-        };
+        Self {
+            kreuzer: value
+                .counts()
+                .map(|(coin, n)| coin.kreuzer * n as u64)
+                .sum(),
+        }
+    }
+}
+
+/// the fewest Mittelreich coins for the amount
+impl From<Money> for Coins {
+    fn from(value: Money) -> Self {
+        Self::exact(value, Currency::Mittelreich)
+    }
+}
+
+impl Display for Coins {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let coins: Vec<String> = self
+            .counts()
+            .filter(|(_, n)| *n > 0)
+            .map(|(coin, n)| format!("{n} {}", if n == 1 { coin.name } else { coin.plural }))
+            .collect();
+        if coins.is_empty() {
+            write!(f, "{NO_COINS}")
+        } else {
+            write!(f, "{}", coins.join(", "))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::{Rng, SeedableRng, rngs::StdRng};
+
+    use super::{Coins, Money};
+
+    #[test]
+    fn random_coins_round_trip() {
+        let mut rng = StdRng::seed_from_u64(1);
+        for _ in 0..2000 {
+            let amount = Money::from_kreuzer(rng.random_range(0..100_000));
+            let coins = Coins::new_random(amount, &mut rng);
+            assert_eq!(Money::from(coins), amount);
+            // no pocket full of Kreuzer
+            assert!(coins.counts[3] < 30, "{coins}");
+        }
+        let rich = Coins::new_random(Money::from_kreuzer(25_000), &mut rng);
+        assert!(rich.counts[0] >= 23);
+    }
+
+    #[test]
+    fn arithmetic_and_parsing() {
+        let price = Money::from_silber(3) + Money::from_heller(5);
+        assert_eq!((price * 4).as_kreuzer(), 1400);
+        assert_eq!(price - Money::from_kreuzer(50), Money::from_silber(3));
+        assert_eq!(Money::from_heller(1).checked_sub(price), None);
+        assert!(Money::from_dukaten(1) > price);
+        assert_eq!(
+            [price, price].iter().sum::<Money>(),
+            Money::from_kreuzer(700)
+        );
+
+        for (text, kreuzer) in [
+            ("3 D 2 S", 3200),
+            ("3D 2S 5H 1K", 3251),
+            ("1 Dukat und 4 Heller", 1040),
+            ("2 Silbertaler, 3 Kreuzer", 203),
+            ("2,5 S", 250),
+            ("0,25 D", 250),
+            ("1.000 Dukaten", 1_000_000),
+            ("12.500 K", 12_500),
+        ] {
+            assert_eq!(
+                text.parse::<Money>().unwrap().as_kreuzer(),
+                kreuzer,
+                "{text}"
+            );
+        }
+        let money = Money::from_kreuzer(12345);
+        assert_eq!(money.to_string().parse::<Money>().unwrap(), money);
+        assert!("3 Gulden".parse::<Money>().is_err());
+        assert!("3".parse::<Money>().is_err());
+        assert!("0,5 K".parse::<Money>().is_err());
+        // a dot groups thousands, it is never the decimal mark
+        for text in ["0.25 D", "1.5 D", "1.00 D", "1000.000 K", "1..000 D"] {
+            assert!(text.parse::<Money>().is_err(), "{text}");
+        }
+        assert!("".parse::<Money>().is_err());
     }
 }
@@ -0,0 +1,177 @@
+//! Blessings, curses and Arcanovi enchantments that turn an ordinary piece of loot into
+//! something special.
+
+use std::fmt::Display;
+
+use rand::{Rng, seq::IndexedRandom};
+
+use crate::items::{LootItemRecord, Rarity};
+
+/// god, what a blessed item of theirs does
+const SEGEN: &[(&str, &str)] = &[
+    ("Praios", "+1 SK gegen Beherrschungszauber"),
+    ("Rondra", "+1 AT im ehrenhaften Zweikampf"),
+    ("Efferd", "bewahrt einmal vor dem Ertrinken"),
+    ("Travia", "Gastfreundschaft wird dem Träger nie verwehrt"),
+    ("Boron", "ruhiger Schlaf ohne Albträume"),
+    ("Hesinde", "+1 auf eine Wissensprobe am Tag"),
+    ("Firun", "+1 auf Fährtensuchen"),
+    ("Tsa", "+1 LeP bei jeder Regeneration"),
+    ("Phex", "+1 auf Taschendiebstahl"),
+    ("Peraine", "+1 auf Heilkunde Wunden"),
+    ("Ingerimm", "+1 auf Metallbearbeitung"),
+    ("Rahja", "+1 auf Betören"),
+    ("Angrosch", "+1 auf Steinbearbeitung"),
+    ("Ifirn", "schützt vor Erfrierungen"),
+    ("Kor", "+1 AT, solange um Sold gekämpft wird"),
+    ("Nandus", "+1 auf Rechnen"),
+];
+
+const FLUECHE: &[&str] = &[
+    "zieht Ratten an",
+    "wird nach Sonnenuntergang eiskalt",
+    "flüstert nachts den Namen des Vorbesitzers",
+    "der Träger verliert jedes Glücksspiel",
+    "Tiere scheuen vor dem Träger",
+    "kehrt immer wieder zum Vorbesitzer zurück",
+];
+
+/// spell, effect, most charges
+const ZAUBER: &[(&str, &str, u8)] = &[
+    ("Balsam Salabunde", "heilt 2W6 LeP", 3),
+    ("Flim Flam", "leuchtet auf Befehl", 10),
+    ("Armatrutz", "RS +2 für eine Stunde", 3),
+    ("Fulminictus", "2W6 SP gegen ein Ziel in Sicht", 2),
+    ("Odem Arcanum", "zeigt Magie in der Nähe", 5),
+    ("Klarum Purum", "neutralisiert ein Gift", 2),
+    ("Penetrizzel", "Blick durch eine Wand", 3),
+    ("Axxeleratus", "doppelte GS für eine Kampfrunde", 3),
+];
+
+/// things that can hold a spell, as opposed to potions, food and paper
+const ENCHANTABLE: &[&str] = &[
+    "schmuck",
+    "waffe",
+    "werkzeug",
+    "instrument",
+    "kleidung",
+    "navigation",
+    "edelstein",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Property {
+    /// only on items dedicated to that god
+    Blessing {
+        god: &'static str,
+        effect: &'static str,
+    },
+    Curse {
+        effect: &'static str,
+    },
+    /// a spell bound with Arcanovi, gone with the last charge
+    Enchantment {
+        spell: &'static str,
+        effect: &'static str,
+        charges: u8,
+    },
+}
+
+impl Display for Property {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Blessing { god, effect } => write!(f, "gesegnet von {god} ({effect})"),
+            Self::Curse { effect } => write!(f, "verflucht ({effect})"),
+            Self::Enchantment {
+                spell,
+                effect,
+                charges,
+            } => write!(
+                f,
+                "{spell} ({effect}), noch {charges} {}",
+                if *charges == 1 { "Ladung" } else { "Ladungen" }
+            ),
+        }
+    }
+}
+
+/// chance for an enchantment and for a curse, rare things are more often special
+fn chances(rarity: Rarity) -> (f64, f64) {
+    match rarity {
+        Rarity::Common => (0.01, 0.01),
+        Rarity::Uncommon => (0.04, 0.02),
+        Rarity::Rare => (0.10, 0.04),
+    }
+}
+
+/// the god an item is dedicated to, if it is a religious one
+fn god(item: &LootItemRecord) -> Option<(&'static str, &'static str)> {
+    if !item.tags.iter().any(|t| t == "religioes") {
+        return None;
+    }
+    SEGEN
+        .iter()
+        .copied()
+        .find(|(god, _)| item.name.ends_with(god))
+}
+
+/// Rolls what is special about one piece of `item`, mostly nothing.
+pub fn roll(rng: &mut impl Rng, item: &LootItemRecord) -> Vec<Property> {
+    let mut properties = vec![];
+    if let Some((god, effect)) = god(item)
+        && rng.random_bool(0.15)
+    {
+        properties.push(Property::Blessing { god, effect });
+    }
+    let (enchantment, curse) = chances(item.rarity);
+    let enchantable = item.tags.iter().any(|t| ENCHANTABLE.contains(&t.as_str()));
+    if enchantable
+        && rng.random_bool(enchantment)
+        && let Some(&(spell, effect, max)) = ZAUBER.choose(rng)
+    {
+        properties.push(Property::Enchantment {
+            spell,
+            effect,
+            charges: rng.random_range(1..=max),
+        });
+    }
+    if rng.random_bool(curse)
+        && let Some(effect) = FLUECHE.choose(rng)
+    {
+        properties.push(Property::Curse { effect });
+    }
+    properties
+}
+
+#[cfg(test)]
+mod test {
+    use rand::{SeedableRng, rngs::StdRng};
+
+    use super::{Property, roll};
+    use crate::items::LootStash;
+
+    #[test]
+    fn only_fitting_properties() {
+        let mut rng = StdRng::seed_from_u64(41);
+        let stash = LootStash::builtin();
+        let mut blessings = 0;
+        for _ in 0..200 {
+            for item in stash.items() {
+                for property in roll(&mut rng, item) {
+                    match property {
+                        Property::Blessing { god, .. } => {
+                            assert!(item.name.ends_with(god), "{} {property}", item.name);
+                            blessings += 1;
+                        }
+                        Property::Enchantment { charges, .. } => {
+                            assert!(charges >= 1);
+                            assert!(!item.tags.iter().any(|t| t == "trank"), "{}", item.name);
+                        }
+                        Property::Curse { .. } => {}
+                    }
+                }
+            }
+        }
+        assert!(blessings > 0);
+    }
+}
@@ -0,0 +1,249 @@
+//! Where a piece of loot comes from and how it can be recognised, and the trouble that
+//! causes when someone tries to sell it.
+
+use std::fmt::Display;
+
+use rand::{Rng, seq::IndexedRandom};
+
+use crate::{
+    Stratum,
+    items::{LootItemRecord, Rarity},
+};
+
+const ADELSHAEUSER: &[&str] = &[
+    "Rabenmund",
+    "Ehrenstein",
+    "Streitzig",
+    "Firdayon",
+    "Bennain",
+    "Gareth",
+];
+
+const HANDELSHAEUSER: &[&str] = &["Stoerrebrandt", "Kolenbrander", "Neersand", "Terdilion"];
+
+/// where the temples are that miss something
+const TEMPELSTAEDTE: &[&str] = &[
+    "Gareth", "Punin", "Havena", "Kuslik", "Festum", "Vinsalt", "Rommilys", "Elenvina",
+];
+
+/// the god in the item's name, or any for religious items without one
+const GOETTER: &[&str] = &[
+    "Praios", "Rondra", "Efferd", "Travia", "Boron", "Hesinde", "Firun", "Tsa", "Phex", "Peraine",
+    "Ingerimm", "Rahja", "Angrosch", "Ifirn", "Kor", "Nandus",
+];
+
+const MARKEN: &[&str] = &[
+    "eingeritzte Initialen",
+    "die Gravur »In ewiger Treue«",
+    "einen Kratzer quer über der Vorderseite",
+    "ein eingeprägtes Tempelsiegel",
+    "eine auffällige Ausbesserung",
+    "ein eingebranntes Zeichen",
+    "einen Namen auf der Unterseite",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum ItemCondition {
+    #[default]
+    New,
+    Worn,
+    Broken,
+}
+
+impl ItemCondition {
+    pub const ALL: [ItemCondition; 3] = [Self::New, Self::Worn, Self::Broken];
+
+    /// value in percent of a new piece
+    pub fn percent(self) -> u64 {
+        match self {
+            Self::New => 100,
+            Self::Worn => 70,
+            Self::Broken => 20,
+        }
+    }
+
+    /// weights for [`ItemCondition::ALL`]
+    fn weights(stratum: Stratum) -> [u32; 3] {
+        match stratum {
+            Stratum::Rich => [70, 28, 2],
+            Stratum::Normal => [30, 65, 5],
+            Stratum::Poor => [10, 75, 15],
+            Stratum::Beggar => [0, 60, 40],
+        }
+    }
+}
+
+impl Display for ItemCondition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::New => "neu",
+            Self::Worn => "abgenutzt",
+            Self::Broken => "beschädigt",
+        };
+        write!(f, "{s}")
+    }
+}
+
+/// who the piece belonged to before the victim, or before the victim stole it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Provenance {
+    /// bears the crest of a noble house
+    Adel {
+        haus: &'static str,
+    },
+    Tempel {
+        gott: &'static str,
+        stadt: &'static str,
+    },
+    Handelshaus {
+        name: &'static str,
+    },
+}
+
+impl Display for Provenance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Adel { haus } => write!(f, "mit dem Wappen des Hauses {haus}"),
+            Self::Tempel { gott, stadt } => write!(f, "aus dem {gott}-Tempel zu {stadt}"),
+            Self::Handelshaus { name } => write!(f, "aus dem Besitz des Handelshauses {name}"),
+        }
+    }
+}
+
+impl Provenance {
+    /// what happens when someone tries to fence it
+    pub fn hook(&self) -> String {
+        match self {
+            Self::Adel { haus } => format!(
+                "Das Haus {haus} lässt nach dem Stück suchen, wer es anbietet, hat bald die Büttel am Hals"
+            ),
+            Self::Tempel { gott, stadt } => format!(
+                "Die Geweihten des {gott}-Tempels zu {stadt} vermissen es, kein Hehler will Ärger mit der Kirche"
+            ),
+            Self::Handelshaus { name } => format!(
+                "Das Handelshaus {name} zahlt eine Belohnung für die Rückgabe, vielleicht mehr als jeder Hehler"
+            ),
+        }
+    }
+}
+
+/// what a piece carries besides its properties
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Flavour {
+    pub condition: ItemCondition,
+    pub provenance: Option<Provenance>,
+    /// makes it recognisable
+    pub marks: Vec<&'static str>,
+}
+
+impl Flavour {
+    /// Provenance and marks only ever show up on single pieces, the rich own more that is
+    /// worth a crest, and only religious or uncommon items have a provenance at all.
+    pub fn roll(rng: &mut impl Rng, item: &LootItemRecord, stratum: Stratum, amount: u32) -> Self {
+        let condition = *ItemCondition::ALL
+            .iter()
+            .zip(ItemCondition::weights(stratum))
+            .collect::<Vec<_>>()
+            .choose_weighted(rng, |(_, w)| *w)
+            .map(|(c, _)| *c)
+            .unwrap_or(&ItemCondition::New);
+        if amount != 1 {
+            return Self {
+                condition,
+                ..Default::default()
+            };
+        }
+        let religious = item.tags.iter().any(|t| t == "religioes");
+        let provenance_chance = match stratum {
+            Stratum::Rich => 0.2,
+            Stratum::Normal => 0.1,
+            Stratum::Poor | Stratum::Beggar => 0.05,
+        };
+        // nobody puts a crest on a piece of chalk
+        let notable = religious || item.rarity != Rarity::Common;
+        let provenance = if !notable || !rng.random_bool(provenance_chance) {
+            None
+        } else if religious {
+            let gott = GOETTER
+                .iter()
+                .find(|g| item.name.ends_with(*g))
+                .or_else(|| GOETTER.choose(rng))
+                .copied();
+            gott.zip(TEMPELSTAEDTE.choose(rng))
+                .map(|(gott, stadt)| Provenance::Tempel { gott, stadt })
+        } else if rng.random_bool(0.6) {
+            ADELSHAEUSER
+                .choose(rng)
+                .map(|haus| Provenance::Adel { haus })
+        } else {
+            HANDELSHAEUSER
+                .choose(rng)
+                .map(|name| Provenance::Handelshaus { name })
+        };
+        let marks = if rng.random_bool(0.15) {
+            MARKEN.choose(rng).into_iter().copied().collect()
+        } else {
+            vec![]
+        };
+        Self {
+            condition,
+            provenance,
+            marks,
+        }
+    }
+
+    /// story hooks for when the party tries to sell the piece
+    pub fn hooks(&self) -> Vec<String> {
+        let mut hooks: Vec<String> = self.provenance.iter().map(Provenance::hook).collect();
+        for mark in &self.marks {
+            hooks.push(format!(
+                "Der Vorbesitzer würde es sofort wiedererkennen, es hat {mark}"
+            ));
+        }
+        if self.condition == ItemCondition::Broken {
+            hooks.push("Der Hehler will erst sehen, ob es sich reparieren lässt".to_string());
+        }
+        hooks
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::{SeedableRng, rngs::StdRng};
+
+    use super::{Flavour, ItemCondition, Provenance};
+    use crate::{Stratum, items::LootStash};
+
+    #[test]
+    fn flavour_fits_the_item() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let stash = LootStash::builtin();
+        let mut provenances = 0;
+        for stratum in Stratum::ALL {
+            for item in stash.items() {
+                for _ in 0..20 {
+                    let flavour = Flavour::roll(&mut rng, item, stratum, 1);
+                    if stratum == Stratum::Beggar {
+                        assert_ne!(flavour.condition, ItemCondition::New);
+                    }
+                    if let Some(Provenance::Tempel { gott, .. }) = flavour.provenance {
+                        assert!(item.tags.iter().any(|t| t == "religioes"));
+                        if item.name.starts_with("Amulett") {
+                            assert!(item.name.ends_with(gott), "{} {gott}", item.name);
+                        }
+                    }
+                    provenances += flavour.provenance.is_some() as usize;
+                    assert_eq!(
+                        flavour.hooks().len(),
+                        flavour.provenance.is_some() as usize
+                            + flavour.marks.len()
+                            + (flavour.condition == ItemCondition::Broken) as usize
+                    );
+                    let many = Flavour::roll(&mut rng, item, stratum, 2);
+                    assert!(many.provenance.is_none() && many.marks.is_empty());
+                }
+            }
+        }
+        assert!(provenances > 0);
+    }
+}
@@ -0,0 +1,174 @@
+//! Weights in Stein and Unzen, and whether someone can carry them.
+//! 1 Stein = 40 Unzen = 1000 g.
+
+use std::{
+    fmt::Display,
+    iter::Sum,
+    ops::{Add, AddAssign, Mul},
+};
+
+pub type Gram = u32;
+
+const GRAM_PER_UNZE: u64 = 25;
+const GRAM_PER_STEIN: u64 = 1000;
+
+/// every coin weighs about this much, whatever it is worth
+pub const COIN_WEIGHT: Weight = Weight::from_gram(10);
+
+/// one more level of Belastung for every started step of this much over the capacity
+const STEIN_PER_BELASTUNG: u64 = 4;
+/// at this level of Belastung nobody takes another step
+pub const MAX_BELASTUNG: u8 = 4;
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Weight {
+    gram: u64,
+}
+
+impl Weight {
+    pub const ZERO: Weight = Weight { gram: 0 };
+
+    pub const fn from_gram(gram: Gram) -> Self {
+        Self { gram: gram as u64 }
+    }
+    pub const fn from_unzen(unzen: u32) -> Self {
+        Self {
+            gram: unzen as u64 * GRAM_PER_UNZE,
+        }
+    }
+    pub const fn from_stein(stein: u32) -> Self {
+        Self {
+            gram: stein as u64 * GRAM_PER_STEIN,
+        }
+    }
+    pub fn as_gram(&self) -> u64 {
+        self.gram
+    }
+}
+
+/// "3 Stein 12 Unzen", Unzen are rounded up so that nothing weighs 0
+impl Display for Weight {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let unzen = self.gram.div_ceil(GRAM_PER_UNZE);
+        let stein = unzen / (GRAM_PER_STEIN / GRAM_PER_UNZE);
+        let unzen = unzen % (GRAM_PER_STEIN / GRAM_PER_UNZE);
+        let plural = if unzen == 1 { "Unze" } else { "Unzen" };
+        match (stein, unzen) {
+            (0, u) => write!(f, "{u} {plural}"),
+            (s, 0) => write!(f, "{s} Stein"),
+            (s, u) => write!(f, "{s} Stein {u} {plural}"),
+        }
+    }
+}
+impl std::fmt::Debug for Weight {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} g", self.gram)
+    }
+}
+
+impl Add for Weight {
+    type Output = Weight;
+    fn add(self, rhs: Weight) -> Weight {
+        Weight {
+            gram: self.gram + rhs.gram,
+        }
+    }
+}
+impl AddAssign for Weight {
+    fn add_assign(&mut self, rhs: Weight) {
+        *self = *self + rhs;
+    }
+}
+/// weight times quantity
+impl Mul<u32> for Weight {
+    type Output = Weight;
+    fn mul(self, quantity: u32) -> Weight {
+        Weight {
+            gram: self.gram * quantity as u64,
+        }
+    }
+}
+impl Sum for Weight {
+    fn sum<I: Iterator<Item = Weight>>(iter: I) -> Weight {
+        iter.fold(Weight::ZERO, Add::add)
+    }
+}
+
+/// Tragkraft: KK × 2 Stein can be carried without Belastung
+pub fn carry_capacity(kk: u8) -> Weight {
+    Weight::from_stein(kk as u32 * 2)
+}
+
+/// levels of Belastung from carrying `load`, up to [`MAX_BELASTUNG`]
+pub fn belastung(load: Weight, kk: u8) -> u8 {
+    let over = load.gram.saturating_sub(carry_capacity(kk).gram);
+    over.div_ceil(STEIN_PER_BELASTUNG * GRAM_PER_STEIN)
+        .min(MAX_BELASTUNG as u64) as u8
+}
+
+/// whether a party can haul `load` away
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Haul {
+    pub load: Weight,
+    /// everyone's Tragkraft together
+    pub capacity: Weight,
+    /// how often they have to walk, 0 if the party is empty
+    pub trips: u32,
+}
+
+impl Haul {
+    /// `kk` of every member of the party
+    pub fn new(load: Weight, kk: &[u8]) -> Self {
+        let capacity: Weight = kk.iter().map(|&kk| carry_capacity(kk)).sum();
+        let trips = if capacity == Weight::ZERO {
+            0
+        } else {
+            load.gram.div_ceil(capacity.gram).max(1) as u32
+        };
+        Self {
+            load,
+            capacity,
+            trips,
+        }
+    }
+
+    /// everything in one go without Belastung
+    pub fn fits(&self) -> bool {
+        self.trips == 1
+    }
+}
+
+impl Display for Haul {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} bei einer Tragkraft von {}", self.load, self.capacity)?;
+        match self.trips {
+            0 => write!(f, ", niemand trägt etwas"),
+            1 => write!(f, ", passt"),
+            n => write!(f, ", {n} Gänge nötig"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Haul, Weight, belastung, carry_capacity};
+
+    #[test]
+    fn stein_and_unzen() {
+        assert_eq!(Weight::from_gram(1).to_string(), "1 Unze");
+        assert_eq!(Weight::from_gram(1300).to_string(), "1 Stein 12 Unzen");
+        assert_eq!(Weight::from_unzen(80).to_string(), "2 Stein");
+        assert_eq!(carry_capacity(13), Weight::from_stein(26));
+
+        assert_eq!(belastung(Weight::from_stein(26), 13), 0);
+        assert_eq!(belastung(Weight::from_gram(26_001), 13), 1);
+        assert_eq!(belastung(Weight::from_stein(100), 13), 4);
+
+        let haul = Haul::new(Weight::from_stein(60), &[12, 14]);
+        assert_eq!(haul.capacity, Weight::from_stein(52));
+        assert_eq!(haul.trips, 2);
+        assert!(!haul.fits());
+        assert!(Haul::new(Weight::from_stein(50), &[12, 14]).fits());
+        assert_eq!(Haul::new(Weight::from_stein(1), &[]).trips, 0);
+    }
+}